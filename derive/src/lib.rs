@@ -0,0 +1,271 @@
+//! Derive macros for [reducer](https://crates.io/crates/reducer).
+//!
+//! This crate is not meant to be used directly,
+//! enable the `derive` feature of reducer instead.
+
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, parse_quote, Data, DeriveInput, Error, Fields, Member, Token};
+
+/// Derives `reducer::Diff` for structs and enums.
+///
+/// The patch of a type `T` is a type named `TPatch` with the same visibility,
+/// which records the patch of every field that changed.
+/// The patch of an enum is a `reducer::EnumPatch`, which either replaces the value
+/// if the variants differ, or updates the fields of the same variant through `TPatch`.
+#[proc_macro_derive(Diff)]
+pub fn derive_diff(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let result = match &input.data {
+        Data::Struct(data) => Ok(derive_struct(&input, &data.fields)),
+        Data::Enum(data) => Ok(derive_enum(&input, &data.variants)),
+        Data::Union(data) => Err(Error::new(
+            data.union_token.span(),
+            "`Diff` cannot be derived for unions",
+        )),
+    };
+
+    result.unwrap_or_else(Error::into_compile_error).into()
+}
+
+/// The members of a struct or variant along with the declarations of their patches.
+struct Members {
+    members: Vec<Member>,
+    patches: Vec<TokenStream>,
+    bindings: (Vec<Ident>, Vec<Ident>, Vec<Ident>),
+}
+
+impl Members {
+    fn new(fields: &Fields) -> Self {
+        let members: Vec<Member> = fields.members().collect();
+
+        let patches = fields
+            .iter()
+            .map(|field| {
+                let (vis, ty) = (&field.vis, &field.ty);
+                let patch = quote!(::core::option::Option<<#ty as ::reducer::Diff>::Patch>);
+
+                match &field.ident {
+                    Some(ident) => quote!(#vis #ident: #patch),
+                    None => quote!(#vis #patch),
+                }
+            })
+            .collect();
+
+        let bind = |prefix: &str| -> Vec<Ident> {
+            (0..members.len())
+                .map(|i| format_ident!("__{}_{}", prefix, i))
+                .collect()
+        };
+
+        let bindings = (bind("self"), bind("other"), bind("patch"));
+
+        Members {
+            members,
+            patches,
+            bindings,
+        }
+    }
+
+    /// The body of a struct or variant declaration.
+    fn declaration(&self, fields: &Fields) -> TokenStream {
+        let patches = &self.patches;
+
+        match fields {
+            Fields::Named(_) => quote!({ #(#patches,)* }),
+            Fields::Unnamed(_) => quote!((#(#patches,)*)),
+            Fields::Unit => quote!(),
+        }
+    }
+
+    /// A pattern that binds every field to the given identifiers.
+    fn pattern(&self, fields: &Fields, bindings: &[Ident]) -> TokenStream {
+        let members = &self.members;
+
+        match fields {
+            Fields::Unit => quote!(),
+            _ => quote!({ #(#members: #bindings,)* }),
+        }
+    }
+}
+
+/// Adds `Diff` bounds for the types of all fields.
+fn where_clause<'a>(
+    input: &DeriveInput,
+    fields: impl IntoIterator<Item = &'a Fields>,
+) -> syn::WhereClause {
+    let mut where_clause = input
+        .generics
+        .where_clause
+        .clone()
+        .unwrap_or_else(|| parse_quote!(where));
+
+    for field in fields.into_iter().flatten() {
+        let ty = &field.ty;
+        where_clause
+            .predicates
+            .push(parse_quote!(#ty: ::reducer::Diff));
+    }
+
+    where_clause
+}
+
+fn derive_struct(input: &DeriveInput, fields: &Fields) -> TokenStream {
+    let (vis, ident, generics) = (&input.vis, &input.ident, &input.generics);
+    let patch = format_ident!("{}Patch", ident);
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let where_clause = where_clause(input, [fields]);
+
+    let members = Members::new(fields);
+    let declaration = members.declaration(fields);
+    let (this, other, patches) = &members.bindings;
+    let this_pattern = members.pattern(fields, this);
+    let other_pattern = members.pattern(fields, other);
+    let patch_pattern = members.pattern(fields, patches);
+
+    let definition = match fields {
+        Fields::Named(_) => quote!(#vis struct #patch #generics #where_clause #declaration),
+        _ => quote!(#vis struct #patch #generics #declaration #where_clause;),
+    };
+
+    let doc = format!(
+        "The [`Diff::Patch`](::reducer::Diff::Patch) of [`{}`].",
+        ident
+    );
+
+    quote! {
+        #[doc = #doc]
+        #[derive(::core::fmt::Debug, ::core::clone::Clone, ::core::cmp::PartialEq)]
+        #definition
+
+        impl #impl_generics ::reducer::Diff for #ident #ty_generics #where_clause {
+            type Patch = #patch #ty_generics;
+
+            fn diff(&self, other: &Self) -> ::core::option::Option<Self::Patch> {
+                let #ident #this_pattern = self;
+                let #ident #other_pattern = other;
+                #(let #patches = ::reducer::Diff::diff(#this, #other);)*
+
+                if true #(&& #patches.is_none())* {
+                    ::core::option::Option::None
+                } else {
+                    ::core::option::Option::Some(#patch #patch_pattern)
+                }
+            }
+
+            fn apply(&mut self, patch: Self::Patch) {
+                let #ident #this_pattern = self;
+                let #patch #patch_pattern = patch;
+
+                #(
+                    if let ::core::option::Option::Some(patch) = #patches {
+                        ::reducer::Diff::apply(#this, patch);
+                    }
+                )*
+            }
+        }
+    }
+}
+
+fn derive_enum(input: &DeriveInput, variants: &Punctuated<syn::Variant, Token![,]>) -> TokenStream {
+    let (vis, ident, generics) = (&input.vis, &input.ident, &input.generics);
+    let patch = format_ident!("{}Patch", ident);
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let mut where_clause = where_clause(input, variants.iter().map(|v| &v.fields));
+
+    where_clause.predicates.push(parse_quote! {
+        #ident #ty_generics: ::core::clone::Clone + ::core::fmt::Debug + ::core::cmp::PartialEq
+    });
+
+    let mut declarations = Vec::new();
+    let mut diffs = Vec::new();
+    let mut applications = Vec::new();
+
+    for variant in variants {
+        let (name, fields) = (&variant.ident, &variant.fields);
+        let members = Members::new(fields);
+        let (this, other, patches) = &members.bindings;
+        let this_pattern = members.pattern(fields, this);
+        let other_pattern = members.pattern(fields, other);
+        let patch_pattern = members.pattern(fields, patches);
+
+        if fields.is_empty() {
+            diffs.push(quote! {
+                (#ident::#name #this_pattern, #ident::#name #other_pattern) => ::core::option::Option::None,
+            });
+
+            continue;
+        }
+
+        let declaration = members.declaration(fields);
+        declarations.push(quote!(#name #declaration));
+
+        diffs.push(quote! {
+            (#ident::#name #this_pattern, #ident::#name #other_pattern) => {
+                #(let #patches = ::reducer::Diff::diff(#this, #other);)*
+
+                if true #(&& #patches.is_none())* {
+                    ::core::option::Option::None
+                } else {
+                    ::core::option::Option::Some(::reducer::EnumPatch::Update(
+                        #patch::#name #patch_pattern,
+                    ))
+                }
+            }
+        });
+
+        applications.push(quote! {
+            (#ident::#name #this_pattern, #patch::#name #patch_pattern) => {
+                #(
+                    if let ::core::option::Option::Some(patch) = #patches {
+                        ::reducer::Diff::apply(#this, patch);
+                    }
+                )*
+            }
+        });
+    }
+
+    let doc = format!(
+        "Updates the fields of a variant of [`{}`], see [`EnumPatch`](::reducer::EnumPatch).",
+        ident
+    );
+
+    quote! {
+        #[doc = #doc]
+        #[derive(::core::fmt::Debug, ::core::clone::Clone, ::core::cmp::PartialEq)]
+        #vis enum #patch #generics #where_clause {
+            #(#declarations,)*
+        }
+
+        impl #impl_generics ::reducer::Diff for #ident #ty_generics #where_clause {
+            type Patch = ::reducer::EnumPatch<Self, #patch #ty_generics>;
+
+            fn diff(&self, other: &Self) -> ::core::option::Option<Self::Patch> {
+                #[allow(unreachable_patterns)]
+                match (self, other) {
+                    #(#diffs)*
+                    _ => ::core::option::Option::Some(::reducer::EnumPatch::Replace(
+                        ::core::clone::Clone::clone(other),
+                    )),
+                }
+            }
+
+            fn apply(&mut self, patch: Self::Patch) {
+                match patch {
+                    ::reducer::EnumPatch::Replace(value) => *self = value,
+
+                    #[allow(unreachable_patterns)]
+                    ::reducer::EnumPatch::Update(patch) => match (self, patch) {
+                        #(#applications)*
+
+                        // Patches of other variants are stale.
+                        _ => {}
+                    },
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use proptest::prelude::*;
+use reducer::{Diff, EnumPatch};
+use std::fmt::Debug;
+use test_strategy::{proptest, Arbitrary};
+
+fn roundtrip<T>(a: T, b: T) -> Result<(), TestCaseError>
+where
+    T: Diff + Debug + Clone + PartialEq,
+{
+    let patch = a.diff(&b);
+    prop_assert_eq!(patch.is_none(), a == b);
+
+    let mut c = a;
+    if let Some(patch) = patch {
+        c.apply(patch);
+    }
+
+    prop_assert_eq!(c, b);
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq, Diff)]
+pub struct Named<T: Debug + Clone + PartialEq> {
+    pub a: u8,
+    pub b: Vec<T>,
+}
+
+#[derive(Debug, Clone, PartialEq, Arbitrary, Diff)]
+struct Tuple(u8, Option<String>);
+
+#[derive(Debug, Clone, PartialEq, Arbitrary, Diff)]
+struct Unit;
+
+#[derive(Debug, Clone, PartialEq, Arbitrary, Diff)]
+enum Choice {
+    A,
+    B(u8, u8),
+    C { x: Vec<u8>, y: Tuple },
+}
+
+#[proptest]
+fn named(a: (u8, Vec<char>), b: (u8, Vec<char>)) {
+    let a = Named { a: a.0, b: a.1 };
+    let b = Named { a: b.0, b: b.1 };
+
+    roundtrip(a.clone(), b.clone())?;
+    roundtrip(a.clone(), a.clone())?;
+
+    let patch = a.diff(&b);
+    let changed = patch.as_ref().and_then(|p| p.a);
+    prop_assert_eq!(changed, (a.a != b.a).then_some(b.a));
+    prop_assert_eq!(patch.and_then(|p| p.b).is_some(), a.b != b.b);
+}
+
+#[proptest]
+fn tuple(a: Tuple, b: Tuple) {
+    roundtrip(a.clone(), b)?;
+    roundtrip(Unit, Unit)?;
+}
+
+#[proptest]
+fn enumeration(a: Choice, b: Choice) {
+    roundtrip(a.clone(), b.clone())?;
+    roundtrip(a.clone(), a.clone())?;
+
+    match (&a, &b, a.diff(&b)) {
+        (Choice::A, Choice::A, patch) => prop_assert_eq!(patch, None),
+        (Choice::B(..), Choice::B(..), Some(EnumPatch::Update(ChoicePatch::B(x, y)))) => {
+            prop_assert!(x.is_some() || y.is_some());
+        }
+        (Choice::C { .. }, Choice::C { .. }, Some(EnumPatch::Update(patch))) => {
+            let updated = matches!(patch, ChoicePatch::C { .. });
+            prop_assert!(updated);
+        }
+        (_, _, Some(EnumPatch::Replace(value))) => prop_assert_eq!(value, b),
+        (_, _, patch) => prop_assert_eq!(patch, None),
+    }
+}
@@ -2,9 +2,9 @@
 
 use eframe::egui::{CentralPanel, Context, Key, ScrollArea, TopBottomPanel};
 use eframe::{run_native, App, Frame, NativeOptions};
-use reducer::{AsyncReactor, Dispatcher, Reducer, Store};
-use ring_channel::{ring_channel, RingReceiver};
-use std::{error::Error, mem, num::NonZeroUsize, sync::Arc};
+use futures::sink::drain;
+use reducer::{AsyncReactor, Dispatcher, Reducer, StateWatcher, Store};
+use std::{error::Error, mem, sync::Arc};
 use tokio::task::spawn;
 
 #[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
@@ -86,16 +86,14 @@ impl Reducer<Action> for State {
 }
 
 struct Application<D: Dispatcher<Action>> {
-    state: Arc<State>,
-    receiver: RingReceiver<Arc<State>>,
+    watcher: StateWatcher<Arc<State>>,
     dispatcher: D,
 }
 
 impl<D: Dispatcher<Action>> Application<D> {
-    fn new(receiver: RingReceiver<Arc<State>>, dispatcher: D) -> Self {
+    fn new(watcher: StateWatcher<Arc<State>>, dispatcher: D) -> Self {
         Application {
-            state: Default::default(),
-            receiver,
+            watcher,
             dispatcher,
         }
     }
@@ -104,14 +102,12 @@ impl<D: Dispatcher<Action>> Application<D> {
 impl<D: Dispatcher<Action>> App for Application<D> {
     fn update(&mut self, ctx: &Context, _: &mut Frame) {
         // Receive our app's latest state.
-        if let Ok(next) = self.receiver.try_recv() {
-            self.state = next;
-        }
+        let state = self.watcher.latest();
 
         // Render the widgets.
         TopBottomPanel::top("header").show(ctx, |ui| {
             ui.horizontal_wrapped(|ui| {
-                let mut input = self.state.get_input().to_string();
+                let mut input = state.get_input().to_string();
                 let text_edit = ui.text_edit_singleline(&mut input);
 
                 if text_edit.changed() {
@@ -130,7 +126,7 @@ impl<D: Dispatcher<Action>> App for Application<D> {
 
         TopBottomPanel::bottom("footer").show(ctx, |ui| {
             ui.horizontal_wrapped(|ui| {
-                let filter = self.state.get_filter();
+                let filter = state.get_filter();
 
                 if ui.toggle_value(&mut (filter == View::All), "All").clicked() {
                     self.dispatcher.dispatch(Action::FilterTodos(View::All));
@@ -155,7 +151,7 @@ impl<D: Dispatcher<Action>> App for Application<D> {
         CentralPanel::default().show(ctx, |ui| {
             ScrollArea::vertical().show(ui, |ui| {
                 ui.vertical_centered_justified(|ui| {
-                    for (id, done, todo) in self.state.get_todos() {
+                    for (id, done, todo) in state.get_todos() {
                         if ui.toggle_value(&mut (!done), todo).clicked() {
                             self.dispatcher.dispatch(Action::ToggleTodo(id));
                         }
@@ -168,14 +164,11 @@ impl<D: Dispatcher<Action>> App for Application<D> {
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
-    // Create a channel that always holds the latest state.
-    let (tx, rx) = ring_channel(NonZeroUsize::new(1).unwrap());
-
     // Create a Store to manage the state.
-    let store = Store::new(Arc::new(State::default()), AsyncReactor(tx));
+    let store = Store::new(Arc::new(State::default()), AsyncReactor(drain()));
 
-    // Turn store into an asynchronous task
-    let (task, dispatcher) = store.into_task();
+    // Turn store into an asynchronous task that publishes the latest state.
+    let (task, dispatcher, watcher) = store.into_watched_task();
 
     // Spawn the asynchronous task on a background thread.
     let handle = spawn(task);
@@ -184,7 +177,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
     run_native(
         "reducer <3 egui",
         NativeOptions::default(),
-        Box::new(|_| Box::new(Application::new(rx, dispatcher))),
+        Box::new(|_| Box::new(Application::new(watcher, dispatcher))),
     )?;
 
     // Wait for the background thread to complete.
@@ -0,0 +1,218 @@
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+use core::time::Duration;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Instant;
+use std::vec::Vec;
+
+/// Trait for sources of time (requires [`async`]).
+///
+/// Reducer is agnostic to the asynchronous runtime,
+/// so timers are provided by implementations of this trait.
+///
+/// [`async`]: index.html#optional-features
+///
+/// # Example
+///
+/// ```rust
+/// use reducer::Clock;
+/// use std::time::{Duration, Instant};
+///
+/// #[derive(Clone)]
+/// struct Tokio;
+///
+/// impl Clock for Tokio {
+///     type Sleep = tokio::time::Sleep;
+///
+///     fn now(&self) -> Instant {
+///         Instant::now()
+///     }
+///
+///     fn sleep(&self, duration: Duration) -> Self::Sleep {
+///         tokio::time::sleep(duration)
+///     }
+/// }
+/// ```
+pub trait Clock {
+    /// The future returned by [`Clock::sleep`].
+    type Sleep: Future<Output = ()>;
+
+    /// Returns the current time.
+    fn now(&self) -> Instant;
+
+    /// Returns a future that completes once `duration` has elapsed.
+    fn sleep(&self, duration: Duration) -> Self::Sleep;
+}
+
+#[derive(Debug)]
+struct Timeline {
+    elapsed: Duration,
+    wakers: Vec<Waker>,
+}
+
+/// A [`Clock`] that only advances when told to (requires [`async`]).
+///
+/// Clones share the same timeline, which makes it possible to drive time deterministically,
+/// typically in tests.
+///
+/// [`async`]: index.html#optional-features
+///
+/// # Example
+///
+/// ```rust
+/// use reducer::{Clock, ManualClock};
+/// use futures::FutureExt;
+/// use std::time::Duration;
+///
+/// let clock = ManualClock::new();
+/// let start = clock.now();
+///
+/// let mut sleep = clock.sleep(Duration::from_secs(3));
+/// assert_eq!((&mut sleep).now_or_never(), None);
+///
+/// clock.advance(Duration::from_secs(2));
+/// assert_eq!((&mut sleep).now_or_never(), None);
+///
+/// clock.advance(Duration::from_secs(1));
+/// assert_eq!((&mut sleep).now_or_never(), Some(()));
+///
+/// assert_eq!(clock.now() - start, Duration::from_secs(3));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    origin: Instant,
+    timeline: Arc<Mutex<Timeline>>,
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ManualClock {
+    /// Constructs a [`ManualClock`] stopped at the current time.
+    pub fn new() -> Self {
+        ManualClock {
+            origin: Instant::now(),
+            timeline: Arc::new(Mutex::new(Timeline {
+                elapsed: Duration::ZERO,
+                wakers: Vec::new(),
+            })),
+        }
+    }
+
+    fn timeline(&self) -> MutexGuard<'_, Timeline> {
+        // The timeline is always left consistent, so it's safe to recover from poisoning.
+        self.timeline.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.timeline().elapsed
+    }
+
+    /// Moves time forward by `duration`, completing any [`Clock::sleep`] that is due.
+    pub fn advance(&self, duration: Duration) {
+        let mut timeline = self.timeline();
+        timeline.elapsed += duration;
+        let wakers = core::mem::take(&mut timeline.wakers);
+        drop(timeline);
+
+        wakers.into_iter().for_each(Waker::wake);
+    }
+}
+
+impl Clock for ManualClock {
+    type Sleep = ManualSleep;
+
+    fn now(&self) -> Instant {
+        self.origin + self.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) -> Self::Sleep {
+        ManualSleep {
+            deadline: self.elapsed() + duration,
+            clock: self.clone(),
+        }
+    }
+}
+
+/// The future returned by [`ManualClock::sleep`](Clock::sleep) (requires [`async`]).
+///
+/// [`async`]: index.html#optional-features
+#[derive(Debug)]
+pub struct ManualSleep {
+    deadline: Duration,
+    clock: ManualClock,
+}
+
+impl Future for ManualSleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut timeline = self.clock.timeline();
+
+        if timeline.elapsed >= self.deadline {
+            Poll::Ready(())
+        } else {
+            if !timeline.wakers.iter().any(|w| w.will_wake(cx.waker())) {
+                timeline.wakers.push(cx.waker().clone());
+            }
+
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::FutureExt;
+    use std::{thread, vec::Vec};
+    use test_strategy::proptest;
+
+    #[proptest]
+    fn now(steps: Vec<u16>) {
+        let clock = ManualClock::default();
+        let start = clock.now();
+        let mut elapsed = Duration::ZERO;
+
+        for &step in &steps {
+            let step = Duration::from_millis(step.into());
+            clock.advance(step);
+            elapsed += step;
+            assert_eq!(clock.now() - start, elapsed);
+            assert_eq!(clock.clone().now() - start, elapsed);
+        }
+    }
+
+    #[proptest]
+    fn sleep(duration: u16, steps: Vec<u16>) {
+        let clock = ManualClock::new();
+        let duration = Duration::from_millis(duration.into());
+        let mut sleep = clock.sleep(duration);
+        let mut elapsed = Duration::ZERO;
+
+        for &step in &steps {
+            let ready = (&mut sleep).now_or_never().is_some();
+            assert_eq!(ready, elapsed >= duration);
+
+            let step = Duration::from_millis(step.into());
+            clock.advance(step);
+            elapsed += step;
+        }
+    }
+
+    #[proptest]
+    fn wake(duration: u16) {
+        let clock = ManualClock::new();
+        let duration = Duration::from_millis(duration.into());
+        let sleep = clock.sleep(duration);
+
+        let handle = thread::spawn(move || futures::executor::block_on(sleep));
+
+        clock.advance(duration);
+        handle.join().unwrap();
+    }
+}
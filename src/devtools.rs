@@ -0,0 +1,844 @@
+use crate::reactor::Reactor;
+use crate::reducer::Reducer;
+use core::any::type_name;
+use core::fmt::{self, Display, Formatter};
+use core::time::Duration;
+use derive_more::Deref;
+use serde::de::DeserializeOwned;
+use serde::ser::{self, Error as _, Impossible, Serialize, Serializer};
+use serde_json::{json, Value};
+use std::error::Error as StdError;
+use std::io::{self, ErrorKind};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::string::{String, ToString};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+use std::thread;
+use std::time::SystemTime;
+use std::vec::Vec;
+use tungstenite::{Message, WebSocket};
+
+/// How long background threads wait on sockets before checking for work.
+const POLL: Duration = Duration::from_millis(10);
+
+/// The name under which [`Store`](crate::Store)s are listed by monitors.
+const NAME: &str = "reducer";
+
+/// A request issued by a Redux DevTools monitor (requires [`devtools`]).
+///
+/// [`devtools`]: index.html#optional-features
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum Command {
+    /// Jump to a previously recorded state, serialized as JSON.
+    ///
+    /// Dispatch the deserialized state wrapped in [`Jump`] to travel back in time.
+    Jump(String),
+
+    /// Dispatch an action typed in the monitor, serialized as JSON.
+    Dispatch(String),
+}
+
+impl Command {
+    /// Deserializes the payload of the command.
+    pub fn parse<T: DeserializeOwned>(&self) -> serde_json::Result<T> {
+        match self {
+            Command::Jump(payload) | Command::Dispatch(payload) => serde_json::from_str(payload),
+        }
+    }
+}
+
+/// An action that replaces the state of an [`Inspected`] [`Store`](crate::Store)
+/// (requires [`devtools`]).
+///
+/// Unlike other actions, jumps are not reported back to monitors.
+///
+/// [`devtools`]: index.html#optional-features
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Jump<S>(pub S);
+
+/// A state whose actions are reported to Redux DevTools monitors (requires [`devtools`]).
+///
+/// [`devtools`]: index.html#optional-features
+#[derive(Debug, Deref)]
+pub struct Inspected<S> {
+    #[deref]
+    state: S,
+    action: Option<serde_json::Result<Value>>,
+}
+
+impl<S> Inspected<S> {
+    /// Returns the state.
+    pub fn into_inner(self) -> S {
+        self.state
+    }
+}
+
+impl<A, S> Reducer<A> for Inspected<S>
+where
+    A: Serialize,
+    S: Reducer<A>,
+{
+    fn reduce(&mut self, action: A) {
+        self.action = Some(serde_json::to_value(&action).map(|value| tag(&action, value)));
+        self.state.reduce(action);
+    }
+}
+
+impl<S> Reducer<Jump<S>> for Inspected<S> {
+    fn reduce(&mut self, Jump(state): Jump<S>) {
+        self.action = None;
+        self.state = state;
+    }
+}
+
+/// Shapes an action after a Redux action, which is identified by its `type` field.
+fn tag<A: Serialize>(action: &A, value: Value) -> Value {
+    match value {
+        Value::Object(fields) if fields.contains_key("type") => Value::Object(fields),
+
+        // Unit variants of externally tagged enums.
+        Value::String(variant) if is_variant(action) => json!({ "type": variant }),
+
+        // Other variants of externally tagged enums.
+        Value::Object(fields) if fields.len() == 1 && is_variant(action) => {
+            let (variant, payload) = fields.into_iter().next().unwrap();
+            json!({ "type": variant, "payload": payload })
+        }
+
+        payload => json!({ "type": type_name::<A>(), "payload": payload }),
+    }
+}
+
+/// Whether the action is serialized as an enum variant, as opposed to e.g. a struct.
+fn is_variant<A: Serialize>(action: &A) -> bool {
+    match action.serialize(Variant) {
+        Ok(()) => false,
+        Err(Detected(variant)) => variant,
+    }
+}
+
+/// Aborts serialization as soon as the shape of the value is known.
+#[derive(Debug)]
+struct Detected(bool);
+
+impl Display for Detected {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("shape detected")
+    }
+}
+
+impl StdError for Detected {}
+
+impl ser::Error for Detected {
+    fn custom<T: Display>(_: T) -> Self {
+        Detected(false)
+    }
+}
+
+/// A [`Serializer`] that detects enum variants, seeing through newtypes and options.
+struct Variant;
+
+macro_rules! not_variant {
+    ($($method:ident($($ty:ty)?)),* $(,)?) => {
+        $(
+            fn $method(self, $(_: $ty)?) -> Result<Self::Ok, Self::Error> {
+                Err(Detected(false))
+            }
+        )*
+    };
+}
+
+impl Serializer for Variant {
+    type Ok = ();
+    type Error = Detected;
+    type SerializeSeq = Impossible<(), Detected>;
+    type SerializeTuple = Impossible<(), Detected>;
+    type SerializeTupleStruct = Impossible<(), Detected>;
+    type SerializeTupleVariant = Impossible<(), Detected>;
+    type SerializeMap = Impossible<(), Detected>;
+    type SerializeStruct = Impossible<(), Detected>;
+    type SerializeStructVariant = Impossible<(), Detected>;
+
+    not_variant! {
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+        serialize_bytes(&[u8]),
+        serialize_none(),
+        serialize_unit(),
+        serialize_unit_struct(&'static str),
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), Detected> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), Detected> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+    ) -> Result<(), Detected> {
+        Err(Detected(true))
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<(), Detected> {
+        Err(Detected(true))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, Detected> {
+        Err(Detected(true))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, Detected> {
+        Err(Detected(true))
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, Detected> {
+        Err(Detected(false))
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, Detected> {
+        Err(Detected(false))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleStruct, Detected> {
+        Err(Detected(false))
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, Detected> {
+        Err(Detected(false))
+    }
+
+    fn serialize_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStruct, Detected> {
+        Err(Detected(false))
+    }
+}
+
+#[derive(Debug)]
+struct Shared {
+    addr: SocketAddr,
+    clients: Mutex<Vec<Sender<String>>>,
+    commands: Mutex<Receiver<Command>>,
+    state: Mutex<Option<String>>,
+    actions: AtomicUsize,
+}
+
+/// Locks a mutex, recovering from poisoning, since the data is always left consistent.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// A server that speaks the [Redux DevTools] remote protocol (requires [`devtools`]).
+///
+/// [`DevTools`] listens for monitors over WebSocket, such as [Remote Redux DevTools],
+/// and reports every action reduced by an [`Inspected`] state along with the resulting state,
+/// which monitors display as a history of actions and state diffs.
+/// To avoid serializing states nobody sees, a monitor that connects while no other is
+/// may only be initialized once the next action is reduced.
+/// [`DevTools`] is a lightweight handle that can be cloned,
+/// all clones refer to the same server, which shuts down once the last one is dropped.
+///
+/// Time travel is driven by the application, which polls [`Command`]s issued by monitors
+/// and dispatches them on the [`Store`](crate::Store).
+/// Only jumping to recorded states and dispatching actions are supported,
+/// other commands, such as toggling past actions, are ignored.
+///
+/// [Redux DevTools]: https://github.com/reduxjs/redux-devtools
+/// [Remote Redux DevTools]: https://github.com/zalmoxisus/remote-redux-devtools
+/// [`devtools`]: index.html#optional-features
+///
+/// # Example
+///
+/// ```rust
+/// use reducer::*;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Default, Serialize, Deserialize)]
+/// struct Counter(i32);
+///
+/// #[derive(Serialize, Deserialize)]
+/// enum Action {
+///     Increment,
+///     Decrement,
+/// }
+///
+/// impl Reducer<Action> for Counter {
+///     fn reduce(&mut self, action: Action) {
+///         match action {
+///             Action::Increment => self.0 += 1,
+///             Action::Decrement => self.0 -= 1,
+///         }
+///     }
+/// }
+///
+/// let devtools = DevTools::bind("127.0.0.1:0").unwrap();
+/// let mut store = Store::new(devtools.inspect(Counter::default()), devtools.clone());
+///
+/// store.dispatch(Action::Increment).unwrap();
+///
+/// // Periodically, e.g. once per frame.
+/// while let Some(command) = devtools.try_recv() {
+///     match command {
+///         Command::Jump(_) => store.dispatch(Jump(command.parse().unwrap())).unwrap(),
+///         Command::Dispatch(_) => store.dispatch(command.parse::<Action>().unwrap()).unwrap(),
+///         _ => {}
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct DevTools {
+    shared: Arc<Shared>,
+}
+
+impl DevTools {
+    /// Starts listening for monitors on the given address.
+    pub fn bind<T: ToSocketAddrs>(addr: T) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+
+        let (sender, receiver) = channel();
+
+        let shared = Arc::new(Shared {
+            addr: listener.local_addr()?,
+            clients: Mutex::new(Vec::new()),
+            commands: Mutex::new(receiver),
+            state: Mutex::new(None),
+            actions: AtomicUsize::new(0),
+        });
+
+        let weak = Arc::downgrade(&shared);
+        thread::spawn(move || listen(listener, weak, sender));
+
+        Ok(DevTools { shared })
+    }
+
+    /// The address the server is listening on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.shared.addr
+    }
+
+    /// Wraps the state, so its actions are reported to monitors.
+    pub fn inspect<S: Serialize>(&self, state: S) -> Inspected<S> {
+        // Errors are reported once the state is first dispatched an action.
+        if let Ok(payload) = serde_json::to_string(&state) {
+            *lock(&self.shared.state) = Some(payload);
+        }
+
+        Inspected {
+            state,
+            action: None,
+        }
+    }
+
+    /// Returns the next [`Command`] issued by a monitor, if any.
+    pub fn try_recv(&self) -> Option<Command> {
+        lock(&self.shared.commands).try_recv().ok()
+    }
+
+    fn publish(&self, message: Value) {
+        let message = message.to_string();
+        let mut clients = lock(&self.shared.clients);
+        clients.retain(|client| client.send(message.clone()).is_ok());
+    }
+}
+
+/// Reports actions along with the resulting state to all connected monitors.
+impl<S: Serialize> Reactor<Inspected<S>> for DevTools {
+    type Error = serde_json::Error;
+
+    fn react(&mut self, state: &Inspected<S>) -> Result<(), Self::Error> {
+        let action = match &state.action {
+            None => None,
+            Some(Err(e)) => return Err(serde_json::Error::custom(e)),
+            Some(Ok(action)) => Some(action),
+        };
+
+        // Nobody would see the state, so there's no point in serializing it.
+        if lock(&self.shared.clients).is_empty() {
+            *lock(&self.shared.state) = None;
+            return Ok(());
+        }
+
+        let payload = serde_json::to_string(&state.state)?;
+
+        // Monitors that connected while the state was out of date are initialized now.
+        if lock(&self.shared.state).replace(payload.clone()).is_none() {
+            self.publish(log(json!({ "type": "INIT", "payload": payload })));
+            return Ok(());
+        }
+
+        let action = match action {
+            None => return Ok(()),
+            Some(action) => action,
+        };
+
+        let timestamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |t| t.as_millis());
+
+        let action = json!({ "type": "PERFORM_ACTION", "action": action, "timestamp": timestamp });
+        let id = self.shared.actions.fetch_add(1, Ordering::Relaxed) + 1;
+
+        self.publish(log(json!({
+            "type": "ACTION",
+            "payload": payload,
+            "action": action.to_string(),
+            "nextActionId": id + 1,
+        })));
+
+        Ok(())
+    }
+}
+
+/// Publishes a message on the channel monitors subscribe to.
+fn log(mut message: Value) -> Value {
+    message["id"] = NAME.into();
+    message["instanceId"] = NAME.into();
+    message["name"] = NAME.into();
+    json!({ "event": "#publish", "data": { "channel": "log", "data": message } })
+}
+
+fn listen(listener: TcpListener, shared: Weak<Shared>, commands: Sender<Command>) {
+    loop {
+        let stream = match listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                if shared.strong_count() == 0 {
+                    return;
+                }
+
+                thread::sleep(POLL);
+                continue;
+            }
+
+            Err(_) => continue,
+        };
+
+        let shared = match shared.upgrade() {
+            Some(shared) => shared,
+            None => return,
+        };
+
+        let (sender, receiver) = channel();
+
+        if let Some(payload) = lock(&shared.state).clone() {
+            let init = log(json!({ "type": "INIT", "payload": payload }));
+            drop(sender.send(init.to_string()));
+        }
+
+        lock(&shared.clients).push(sender);
+
+        let commands = commands.clone();
+        thread::spawn(move || connect(stream, receiver, commands));
+    }
+}
+
+fn connect(stream: TcpStream, outgoing: Receiver<String>, commands: Sender<Command>) {
+    let ws = stream
+        .set_nonblocking(false)
+        .ok()
+        .and_then(|()| tungstenite::accept(stream).ok());
+
+    if let Some(mut ws) = ws {
+        if ws.get_ref().set_read_timeout(Some(POLL)).is_ok() {
+            serve(&mut ws, &outgoing, &commands);
+        }
+
+        drop(ws.close(None));
+        drop(ws.flush());
+    }
+}
+
+fn serve(ws: &mut WebSocket<TcpStream>, outgoing: &Receiver<String>, commands: &Sender<Command>) {
+    loop {
+        loop {
+            match outgoing.try_recv() {
+                Ok(message) => {
+                    if ws.write(Message::Text(message)).is_err() {
+                        return;
+                    }
+                }
+
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => return,
+            }
+        }
+
+        if ws.flush().is_err() {
+            return;
+        }
+
+        let text = match ws.read() {
+            Ok(Message::Text(text)) => text,
+            Ok(_) => continue,
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) =>
+            {
+                continue
+            }
+
+            Err(_) => return,
+        };
+
+        let (reply, command) = receive(&text);
+
+        if let Some(reply) = reply {
+            if ws.write(Message::Text(reply)).is_err() {
+                return;
+            }
+        }
+
+        if let Some(command) = command {
+            drop(commands.send(command));
+        }
+    }
+}
+
+/// Interprets a message sent by a monitor, returning the reply and the [`Command`], if any.
+fn receive(text: &str) -> (Option<String>, Option<Command>) {
+    // Heartbeat.
+    if text == "#1" {
+        return (Some("#2".into()), None);
+    }
+
+    let message: Value = match serde_json::from_str(text) {
+        Ok(message) => message,
+        Err(_) => return (None, None),
+    };
+
+    let reply = message["cid"]
+        .as_u64()
+        .map(|cid| match message["event"].as_str() {
+            Some("#handshake") => json!({
+                "rid": cid,
+                "data": { "id": NAME, "pingTimeout": 20000, "isAuthenticated": false },
+            }),
+
+            _ => json!({ "rid": cid }),
+        });
+
+    // Messages may be wrapped in SocketCluster events or sent bare.
+    let data = match message["event"].as_str() {
+        Some("#publish") => &message["data"]["data"],
+        Some(_) => &message["data"],
+        None => &message,
+    };
+
+    let command = match data["type"].as_str() {
+        Some("DISPATCH") => match data["payload"]["type"].as_str() {
+            Some("JUMP_TO_STATE" | "JUMP_TO_ACTION") => {
+                data["state"].as_str().map(|s| Command::Jump(s.into()))
+            }
+
+            _ => None,
+        },
+
+        Some("ACTION") => match &data["action"] {
+            Value::String(action) => Some(Command::Dispatch(action.clone())),
+            Value::Null => None,
+            action => Some(Command::Dispatch(action.to_string())),
+        },
+
+        _ => None,
+    };
+
+    (reply.map(|r| r.to_string()), command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dispatcher::{Dispatcher, Store};
+    use crate::reducer::MockReducer;
+    use mockall::predicate::*;
+    use serde::{Deserialize, Serialize};
+    use std::time::Instant;
+    use test_strategy::proptest;
+    use tungstenite::stream::MaybeTlsStream;
+
+    type Client = WebSocket<MaybeTlsStream<TcpStream>>;
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Counter(u8);
+
+    impl Reducer<u8> for Counter {
+        fn reduce(&mut self, action: u8) {
+            self.0 = self.0.wrapping_add(action);
+        }
+    }
+
+    fn next(client: &mut Client) -> Value {
+        loop {
+            if let Message::Text(text) = client.read().unwrap() {
+                return serde_json::from_str(&text).unwrap();
+            }
+        }
+    }
+
+    fn poll(devtools: &DevTools) -> Command {
+        let start = Instant::now();
+
+        loop {
+            if let Some(command) = devtools.try_recv() {
+                return command;
+            }
+
+            assert!(start.elapsed() < Duration::from_secs(5));
+            thread::sleep(POLL);
+        }
+    }
+
+    #[proptest]
+    fn tag(a: u8, s: String) {
+        #[derive(Serialize)]
+        enum Action {
+            Unit,
+            Tuple(u8),
+        }
+
+        #[derive(Serialize)]
+        struct Wrapper(Action);
+
+        let tagged = |v| super::tag(&v, serde_json::to_value(&v).unwrap());
+
+        assert_eq!(tagged(Action::Unit), json!({ "type": "Unit" }));
+        assert_eq!(
+            tagged(Action::Tuple(a)),
+            json!({ "type": "Tuple", "payload": a })
+        );
+
+        assert_eq!(
+            super::tag(&Wrapper(Action::Tuple(a)), json!({ "Tuple": a })),
+            json!({ "type": "Tuple", "payload": a })
+        );
+
+        assert_eq!(
+            super::tag(&a, json!(a)),
+            json!({ "type": "u8", "payload": a })
+        );
+
+        assert_eq!(
+            super::tag(&s, json!(s)),
+            json!({ "type": type_name::<String>(), "payload": s })
+        );
+
+        assert_eq!(
+            super::tag(&(), json!({ "type": s, "x": a })),
+            json!({ "type": s, "x": a })
+        );
+    }
+
+    #[proptest]
+    fn untagged(x: i32) {
+        #[derive(Serialize)]
+        struct Add {
+            x: i32,
+        }
+
+        assert_eq!(
+            super::tag(&Add { x }, serde_json::to_value(Add { x }).unwrap()),
+            json!({ "type": type_name::<Add>(), "payload": { "x": x } })
+        );
+    }
+
+    #[proptest]
+    fn receive(cid: u64, state: String, action: String) {
+        assert_eq!(super::receive("#1"), (Some("#2".into()), None));
+
+        let handshake = json!({ "event": "#handshake", "data": {}, "cid": cid });
+        let (reply, command) = super::receive(&handshake.to_string());
+        let reply: Value = serde_json::from_str(&reply.unwrap())?;
+        assert_eq!(reply["rid"], cid);
+        assert_eq!(reply["data"]["id"], NAME);
+        assert_eq!(command, None);
+
+        let jump = json!({
+            "event": "#publish",
+            "data": {
+                "channel": "respond",
+                "data": { "type": "DISPATCH", "payload": { "type": "JUMP_TO_STATE" }, "state": state },
+            },
+        });
+
+        assert_eq!(
+            super::receive(&jump.to_string()),
+            (None, Some(Command::Jump(state)))
+        );
+
+        let dispatch = json!({ "event": "respond", "data": { "type": "ACTION", "action": action }, "cid": cid });
+        let (reply, command) = super::receive(&dispatch.to_string());
+        assert_eq!(reply, Some(json!({ "rid": cid }).to_string()));
+        assert_eq!(command, Some(Command::Dispatch(action)));
+
+        let toggle =
+            json!({ "type": "DISPATCH", "payload": { "type": "TOGGLE_ACTION", "id": cid } });
+        assert_eq!(super::receive(&toggle.to_string()), (None, None));
+    }
+
+    #[proptest]
+    #[cfg_attr(miri, ignore)] // Sockets are not supported.
+    fn inspect(state: u8, action: u8, other: u8) {
+        let mut mock = MockReducer::<u8>::new();
+        mock.expect_reduce()
+            .with(eq(action))
+            .once()
+            .return_const(());
+
+        let mut reducer = Inspected {
+            state: mock,
+            action: None,
+        };
+
+        reducer.reduce(action);
+        assert_eq!(
+            reducer.action.as_ref().unwrap().as_ref().unwrap(),
+            &json!({ "type": "u8", "payload": action })
+        );
+
+        let devtools = DevTools::bind("127.0.0.1:0")?;
+        let mut reducer = devtools.inspect(Counter(state));
+        assert_eq!(*lock(&devtools.shared.state), Some(state.to_string()));
+        assert!(reducer.action.is_none());
+
+        reducer.reduce(Jump(Counter(other)));
+        assert_eq!(reducer.0, other);
+        assert!(reducer.action.is_none());
+        assert_eq!(reducer.into_inner().0, other);
+    }
+
+    #[proptest]
+    #[cfg_attr(miri, ignore)] // Sockets are not supported.
+    fn idle(state: u8, action: u8) {
+        let devtools = DevTools::bind("127.0.0.1:0")?;
+        let mut store = Store::new(devtools.inspect(Counter(state)), devtools.clone());
+
+        // Nobody is watching.
+        store.dispatch(action)?;
+        assert_eq!(*lock(&devtools.shared.state), None);
+
+        let url = std::format!("ws://{}", devtools.local_addr());
+        let (mut client, _) = tungstenite::connect(url)?;
+
+        let start = Instant::now();
+        while lock(&devtools.shared.clients).is_empty() {
+            assert!(start.elapsed() < Duration::from_secs(5));
+            thread::sleep(POLL);
+        }
+
+        let state = state.wrapping_add(action).wrapping_add(action);
+        store.dispatch(action)?;
+
+        let init = &next(&mut client)["data"]["data"];
+        assert_eq!(init["type"], "INIT");
+        assert_eq!(init["payload"], state.to_string());
+    }
+
+    #[proptest]
+    #[cfg_attr(miri, ignore)] // Sockets are not supported.
+    fn remote(state: u8, action: u8, jump: u8) {
+        let devtools = DevTools::bind("127.0.0.1:0")?;
+        let mut store = Store::new(devtools.inspect(Counter(state)), devtools.clone());
+
+        let url = std::format!("ws://{}", devtools.local_addr());
+        let (mut client, _) = tungstenite::connect(url)?;
+
+        let init = next(&mut client);
+        assert_eq!(init["event"], "#publish");
+        assert_eq!(init["data"]["channel"], "log");
+        assert_eq!(init["data"]["data"]["type"], "INIT");
+        assert_eq!(init["data"]["data"]["payload"], state.to_string());
+
+        client.send(Message::Text(
+            json!({ "event": "#handshake", "data": {}, "cid": 1 }).to_string(),
+        ))?;
+
+        assert_eq!(next(&mut client)["rid"], 1);
+
+        let state = state.wrapping_add(action);
+        store.dispatch(action)?;
+
+        let log = &next(&mut client)["data"]["data"];
+        assert_eq!(log["type"], "ACTION");
+        assert_eq!(log["payload"], state.to_string());
+        assert_eq!(log["nextActionId"], 2);
+
+        let performed: Value = serde_json::from_str(log["action"].as_str().unwrap())?;
+        assert_eq!(performed["type"], "PERFORM_ACTION");
+        assert_eq!(
+            performed["action"],
+            json!({ "type": "u8", "payload": action })
+        );
+
+        client.send(Message::Text(
+            json!({
+                "type": "DISPATCH",
+                "payload": { "type": "JUMP_TO_ACTION" },
+                "state": jump.to_string(),
+            })
+            .to_string(),
+        ))?;
+
+        let command = poll(&devtools);
+        assert_eq!(command, Command::Jump(jump.to_string()));
+        store.dispatch(Jump(command.parse::<Counter>()?))?;
+        assert_eq!(store.0, jump);
+
+        client.send(Message::Text(
+            json!({ "type": "ACTION", "action": action.to_string() }).to_string(),
+        ))?;
+
+        let command = poll(&devtools);
+        assert_eq!(command, Command::Dispatch(action.to_string()));
+        store.dispatch(command.parse::<u8>()?)?;
+        assert_eq!(store.0, jump.wrapping_add(action));
+    }
+}
@@ -0,0 +1,436 @@
+use core::fmt::Debug;
+
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, collections::BTreeMap, string::String, vec::Vec};
+
+#[cfg(feature = "std")]
+use std::{collections::HashMap, hash::BuildHasher, hash::Hash};
+
+/// Derives [`Diff`] for structs and enums (requires [`derive`]).
+///
+/// The [`Diff::Patch`] of a struct `T` is a struct named `TPatch` with the same shape,
+/// which records the patch of every field that changed,
+/// while the [`Diff::Patch`] of an enum `T` is an [`EnumPatch`],
+/// which updates the fields of the same variant through an enum named `TPatch`.
+///
+/// [`derive`]: index.html#optional-features
+#[cfg(feature = "derive")]
+pub use reducer_derive::Diff;
+
+/// Trait for types that can describe how they differ from one another.
+///
+/// Implementations are provided for primitive types, [`Option`], and,
+/// if [`alloc`] is enabled, [`String`], [`Box`], [`Vec`] and [`BTreeMap`],
+/// as well as [`HashMap`](std::collections::HashMap) if [`std`] is enabled.
+/// Enable the [`derive`] feature to derive [`Diff`] for structs and enums.
+///
+/// [`alloc`]: index.html#optional-features
+/// [`std`]: index.html#optional-features
+/// [`derive`]: index.html#optional-features
+///
+/// # Example
+///
+#[cfg_attr(feature = "alloc", doc = "```rust")]
+#[cfg_attr(not(feature = "alloc"), doc = "```ignore")]
+/// use reducer::*;
+///
+/// let mut todos = vec!["write docs", "fix bugs"];
+/// let done = vec!["write docs"];
+///
+/// let patch = todos.diff(&done).unwrap();
+/// assert_eq!(patch, vec![VecEdit::Truncate(1)]);
+///
+/// todos.apply(patch);
+/// assert_eq!(todos, done);
+/// assert_eq!(todos.diff(&done), None);
+/// ```
+pub trait Diff {
+    /// A description of the changes between two values.
+    type Patch: Debug + Clone + PartialEq;
+
+    /// Returns the changes that turn `self` into `other`, or `None` if they are equal.
+    fn diff(&self, other: &Self) -> Option<Self::Patch>;
+
+    /// Applies changes described by a patch.
+    ///
+    /// Applying the patch returned by [`Diff::diff`] turns `self` into `other`.
+    fn apply(&mut self, patch: Self::Patch);
+}
+
+macro_rules! impl_diff_for_values {
+    ( $($type:ty),* $(,)? ) => {
+        $(
+            /// Values are replaced as a whole.
+            impl Diff for $type {
+                type Patch = Self;
+
+                fn diff(&self, other: &Self) -> Option<Self::Patch> {
+                    (self != other).then(|| other.clone())
+                }
+
+                fn apply(&mut self, patch: Self::Patch) {
+                    *self = patch;
+                }
+            }
+        )*
+    };
+}
+
+impl_diff_for_values!(
+    (),
+    bool,
+    char,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    &'static str,
+);
+
+macro_rules! impl_diff_for_floats {
+    ( $($type:ty),* $(,)? ) => {
+        $(
+            /// Values are replaced as a whole.
+            ///
+            /// Values are compared bitwise, so `NaN` is equal to itself,
+            /// while `0.0` and `-0.0` differ.
+            impl Diff for $type {
+                type Patch = Self;
+
+                fn diff(&self, other: &Self) -> Option<Self::Patch> {
+                    (self.to_bits() != other.to_bits()).then_some(*other)
+                }
+
+                fn apply(&mut self, patch: Self::Patch) {
+                    *self = patch;
+                }
+            }
+        )*
+    };
+}
+
+impl_diff_for_floats!(f32, f64);
+
+#[cfg(feature = "alloc")]
+impl_diff_for_values!(String);
+
+/// The [`Diff::Patch`] of enums.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum EnumPatch<T, P> {
+    /// Replaces the value, if the variants differ.
+    Replace(T),
+
+    /// Updates the fields of the variant, if the variants are the same.
+    Update(P),
+}
+
+impl<T> Diff for Option<T>
+where
+    T: Diff + Debug + Clone + PartialEq,
+{
+    type Patch = EnumPatch<Self, T::Patch>;
+
+    fn diff(&self, other: &Self) -> Option<Self::Patch> {
+        match (self, other) {
+            (Some(a), Some(b)) => a.diff(b).map(EnumPatch::Update),
+            (None, None) => None,
+            _ => Some(EnumPatch::Replace(other.clone())),
+        }
+    }
+
+    fn apply(&mut self, patch: Self::Patch) {
+        match (self, patch) {
+            (this, EnumPatch::Replace(value)) => *this = value,
+            (Some(value), EnumPatch::Update(patch)) => value.apply(patch),
+            (None, EnumPatch::Update(_)) => {}
+        }
+    }
+}
+
+/// Diffs the potentially _unsized_ boxed value (requires [`alloc`]).
+///
+/// [`alloc`]: index.html#optional-features
+#[cfg(feature = "alloc")]
+impl<T> Diff for Box<T>
+where
+    T: Diff + ?Sized,
+{
+    type Patch = T::Patch;
+
+    fn diff(&self, other: &Self) -> Option<Self::Patch> {
+        (**self).diff(&**other)
+    }
+
+    fn apply(&mut self, patch: Self::Patch) {
+        (**self).apply(patch);
+    }
+}
+
+/// An edit of a [`Vec`], see [`Diff`] (requires [`alloc`]).
+///
+/// [`alloc`]: index.html#optional-features
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq)]
+pub enum VecEdit<T: Diff> {
+    /// Updates the element at an index.
+    Update(usize, T::Patch),
+
+    /// Shortens the [`Vec`] to a length.
+    Truncate(usize),
+
+    /// Appends an element.
+    Push(T),
+}
+
+/// Diffs elements at the same index (requires [`alloc`]).
+///
+/// [`alloc`]: index.html#optional-features
+#[cfg(feature = "alloc")]
+impl<T> Diff for Vec<T>
+where
+    T: Diff + Debug + Clone + PartialEq,
+{
+    type Patch = Vec<VecEdit<T>>;
+
+    fn diff(&self, other: &Self) -> Option<Self::Patch> {
+        let mut edits: Vec<_> = self
+            .iter()
+            .zip(other)
+            .enumerate()
+            .filter_map(|(i, (a, b))| Some(VecEdit::Update(i, a.diff(b)?)))
+            .collect();
+
+        if other.len() < self.len() {
+            edits.push(VecEdit::Truncate(other.len()));
+        }
+
+        let appended = other.get(self.len()..).unwrap_or_default();
+        edits.extend(appended.iter().cloned().map(VecEdit::Push));
+
+        (!edits.is_empty()).then_some(edits)
+    }
+
+    fn apply(&mut self, patch: Self::Patch) {
+        for edit in patch {
+            match edit {
+                VecEdit::Update(i, patch) => self[i].apply(patch),
+                VecEdit::Truncate(len) => self.truncate(len),
+                VecEdit::Push(value) => self.push(value),
+            }
+        }
+    }
+}
+
+/// An edit of a map, see [`Diff`] (requires [`alloc`]).
+///
+/// [`alloc`]: index.html#optional-features
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq)]
+pub enum MapEdit<K, V: Diff> {
+    /// Inserts an entry.
+    Insert(K, V),
+
+    /// Updates the value of an entry.
+    Update(K, V::Patch),
+
+    /// Removes an entry.
+    Remove(K),
+}
+
+#[cfg(feature = "alloc")]
+macro_rules! impl_diff_for_map {
+    ( $self:ident, $other:ident ) => {
+        fn diff(&$self, $other: &Self) -> Option<Self::Patch> {
+            let mut edits = Vec::new();
+
+            for (k, v) in $self {
+                match $other.get(k) {
+                    None => edits.push(MapEdit::Remove(k.clone())),
+                    Some(o) => edits.extend(v.diff(o).map(|p| MapEdit::Update(k.clone(), p))),
+                }
+            }
+
+            for (k, o) in $other {
+                if !$self.contains_key(k) {
+                    edits.push(MapEdit::Insert(k.clone(), o.clone()));
+                }
+            }
+
+            (!edits.is_empty()).then_some(edits)
+        }
+
+        fn apply(&mut $self, patch: Self::Patch) {
+            for edit in patch {
+                match edit {
+                    MapEdit::Insert(k, v) => drop($self.insert(k, v)),
+                    MapEdit::Update(k, p) => {
+                        if let Some(v) = $self.get_mut(&k) {
+                            v.apply(p);
+                        }
+                    }
+
+                    MapEdit::Remove(k) => drop($self.remove(&k)),
+                }
+            }
+        }
+    };
+}
+
+/// Diffs values with the same key (requires [`alloc`]).
+///
+/// [`alloc`]: index.html#optional-features
+#[cfg(feature = "alloc")]
+impl<K, V> Diff for BTreeMap<K, V>
+where
+    K: Ord + Debug + Clone,
+    V: Diff + Debug + Clone + PartialEq,
+{
+    type Patch = Vec<MapEdit<K, V>>;
+
+    impl_diff_for_map!(self, other);
+}
+
+/// Diffs values with the same key (requires [`std`]).
+///
+/// [`std`]: index.html#optional-features
+#[cfg(feature = "std")]
+impl<K, V, S> Diff for HashMap<K, V, S>
+where
+    K: Eq + Hash + Debug + Clone,
+    V: Diff + Debug + Clone + PartialEq,
+    S: BuildHasher,
+{
+    type Patch = Vec<MapEdit<K, V>>;
+
+    impl_diff_for_map!(self, other);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use test_strategy::proptest;
+
+    #[cfg(feature = "alloc")]
+    use std::{string::String, vec::Vec};
+
+    fn roundtrip<T>(a: T, b: T) -> Result<(), TestCaseError>
+    where
+        T: Diff + Debug + Clone + PartialEq,
+    {
+        let patch = a.diff(&b);
+        prop_assert_eq!(patch.is_none(), a == b);
+
+        let mut c = a;
+        if let Some(patch) = patch {
+            c.apply(patch);
+        }
+
+        prop_assert_eq!(c, b);
+        Ok(())
+    }
+
+    #[proptest]
+    fn values(a: u8, b: u8, c: char, d: char, x: Option<i32>, y: Option<i32>) {
+        roundtrip(a, b)?;
+        roundtrip(a, a)?;
+        roundtrip(c, d)?;
+        roundtrip(x, y)?;
+        roundtrip(x, x)?;
+    }
+
+    #[proptest]
+    fn floats(a: f64, b: f32) {
+        prop_assert_eq!(a.diff(&a).map(f64::to_bits), None);
+        prop_assert_eq!(b.diff(&b).map(f32::to_bits), None);
+
+        prop_assert_eq!(f64::NAN.diff(&f64::NAN).map(f64::to_bits), None);
+        prop_assert_eq!(f32::NAN.diff(&f32::NAN).map(f32::to_bits), None);
+        prop_assert_eq!(
+            0.0f64.diff(&-0.0).map(f64::to_bits),
+            Some((-0.0f64).to_bits())
+        );
+
+        let mut c = f64::NAN;
+        if let Some(patch) = c.diff(&a) {
+            c.apply(patch);
+        }
+
+        prop_assert_eq!(c.to_bits(), a.to_bits());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[proptest]
+    fn string(a: String, b: String) {
+        roundtrip(a.clone(), b)?;
+        roundtrip(a.clone(), a)?;
+    }
+
+    #[cfg(feature = "alloc")]
+    #[proptest]
+    fn boxed(a: Vec<u8>, b: Vec<u8>) {
+        roundtrip(Box::new(a), Box::new(b))?;
+    }
+
+    #[cfg(feature = "alloc")]
+    #[proptest]
+    fn vec(a: Vec<Option<u8>>, b: Vec<Option<u8>>) {
+        roundtrip(a.clone(), b.clone())?;
+        roundtrip(a.clone(), a.clone())?;
+
+        let patch = a.diff(&b).unwrap_or_default();
+        let pushed = patch
+            .iter()
+            .filter(|e| matches!(e, VecEdit::Push(_)))
+            .count();
+        let truncated = patch
+            .iter()
+            .filter(|e| matches!(e, VecEdit::Truncate(_)))
+            .count();
+
+        prop_assert_eq!(pushed, b.len().saturating_sub(a.len()));
+        prop_assert_eq!(truncated, usize::from(b.len() < a.len()));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[proptest]
+    fn btree_map(a: BTreeMap<u8, Vec<u8>>, b: BTreeMap<u8, Vec<u8>>) {
+        roundtrip(a.clone(), b.clone())?;
+        roundtrip(a.clone(), a.clone())?;
+
+        let patch = a.diff(&b).unwrap_or_default();
+        let removed = a.keys().filter(|k| !b.contains_key(k)).count();
+        let inserted = b.keys().filter(|k| !a.contains_key(k)).count();
+
+        prop_assert_eq!(
+            patch
+                .iter()
+                .filter(|e| matches!(e, MapEdit::Remove(_)))
+                .count(),
+            removed
+        );
+
+        prop_assert_eq!(
+            patch
+                .iter()
+                .filter(|e| matches!(e, MapEdit::Insert(..)))
+                .count(),
+            inserted
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[proptest]
+    fn hash_map(a: HashMap<u8, u8>, b: HashMap<u8, u8>) {
+        roundtrip(a.clone(), b)?;
+        roundtrip(a.clone(), a)?;
+    }
+}
@@ -1,10 +1,64 @@
+mod array;
+#[cfg(feature = "alloc")]
+mod convergent;
+mod ext;
+#[cfg(feature = "ipc")]
+mod ipc;
+#[cfg(feature = "alloc")]
+mod local;
+#[cfg(feature = "alloc")]
+mod queue;
+#[cfg(feature = "replication")]
+mod replication;
+#[cfg(feature = "alloc")]
+mod router;
+#[cfg(feature = "async")]
+mod scheduler;
+mod scope;
+#[cfg(feature = "std")]
+mod shared;
 #[cfg(feature = "async")]
 mod sink;
+#[cfg(feature = "alloc")]
+mod slice;
 mod store;
+#[cfg(feature = "async")]
+mod task;
+#[cfg(feature = "alloc")]
+mod time_travel;
+mod tuple;
+#[cfg(feature = "alloc")]
+mod vec;
+#[cfg(feature = "async")]
+mod watcher;
 
+#[cfg(feature = "alloc")]
+pub use self::convergent::*;
+pub use self::ext::*;
+#[cfg(feature = "ipc")]
+pub use self::ipc::*;
+#[cfg(feature = "alloc")]
+pub use self::local::*;
+#[cfg(feature = "alloc")]
+pub use self::queue::*;
+#[cfg(feature = "replication")]
+pub use self::replication::*;
+#[cfg(feature = "alloc")]
+pub use self::router::*;
+#[cfg(feature = "async")]
+pub use self::scheduler::*;
+pub use self::scope::*;
+#[cfg(feature = "std")]
+pub use self::shared::*;
 #[cfg(feature = "async")]
 pub use self::sink::*;
 pub use self::store::*;
+#[cfg(feature = "async")]
+pub use self::task::*;
+#[cfg(feature = "alloc")]
+pub use self::time_travel::*;
+#[cfg(feature = "async")]
+pub use self::watcher::StateWatcher;
 
 /// Trait for types that allow dispatching actions.
 pub trait Dispatcher<A> {
@@ -68,5 +122,4 @@ mod tests {
 }
 
 #[cfg(test)]
-#[cfg(feature = "async")]
 pub(crate) use self::tests::MockDispatcher;
@@ -0,0 +1,67 @@
+use crate::dispatcher::*;
+
+/// Dispatches a clone of the action to all [`Dispatcher`]s in the array in order,
+/// collecting their outputs.
+///
+/// # Example
+///
+/// ```rust
+/// use reducer::*;
+///
+/// #[derive(Clone)]
+/// struct Action { /* ... */ }
+///
+/// struct Channel { /* ... */ }
+///
+/// impl Dispatcher<Action> for Channel {
+///     type Output = bool;
+///     fn dispatch(&mut self, action: Action) -> Self::Output {
+///         // ...
+///         true
+///     }
+/// }
+///
+/// let a = Channel { /* ... */ };
+/// let b = Channel { /* ... */ };
+/// // ...
+/// let z = Channel { /* ... */ };
+///
+/// let mut channels = [a, b, /* ..., */ z];
+///
+/// // All channels receive the action.
+/// let [a, b, /* ..., */ z] = channels.dispatch(Action { /* ... */ });
+/// ```
+impl<A, D, const N: usize> Dispatcher<A> for [D; N]
+where
+    A: Clone,
+    D: Dispatcher<A>,
+{
+    type Output = [D::Output; N];
+
+    fn dispatch(&mut self, action: A) -> Self::Output {
+        self.each_mut().map(|d| d.dispatch(action.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockall::{predicate::*, Sequence};
+    use test_strategy::proptest;
+
+    #[proptest]
+    fn dispatch(action: u8, results: [u8; 32]) {
+        let mut seq = Sequence::new();
+        let mut dispatcher: [MockDispatcher<u8, u8>; 32] = Default::default();
+
+        for (mock, &result) in dispatcher.iter_mut().zip(&results) {
+            mock.expect_dispatch()
+                .with(eq(action))
+                .once()
+                .in_sequence(&mut seq)
+                .return_const(result);
+        }
+
+        assert_eq!(Dispatcher::dispatch(&mut dispatcher, action), results);
+    }
+}
@@ -0,0 +1,571 @@
+use crate::dispatcher::{Dispatcher, Store};
+use crate::mergeable::Mergeable;
+use crate::reactor::Reactor;
+use crate::reducer::Reducer;
+use alloc::vec::Vec;
+use core::ops::Deref;
+
+/// The default number of actions between checkpoints.
+const INTERVAL: usize = 32;
+
+/// A Lamport timestamp that totally orders actions across replicas (requires [`alloc`]).
+///
+/// Stamps are ordered by time first and ties are broken by the replica id,
+/// so replicas must be assigned distinct ids.
+///
+/// [`alloc`]: index.html#optional-features
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Stamp {
+    /// The logical time.
+    pub time: u64,
+
+    /// The id of the replica that originated the action.
+    pub replica: u64,
+}
+
+/// An action tagged with a [`Stamp`] (requires [`alloc`]).
+///
+/// [`alloc`]: index.html#optional-features
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Stamped<A> {
+    /// When and where the action originated.
+    pub stamp: Stamp,
+
+    /// The action.
+    pub action: A,
+}
+
+/// The state that accounts for every action up to a [`Stamp`] (requires [`alloc`]).
+///
+/// Once a prefix of the log has been [compacted], late replicas may no longer catch up by
+/// dispatching the log alone, so replicas whose states are [`Mergeable`] exchange their
+/// [baselines] too.
+///
+/// [`alloc`]: index.html#optional-features
+/// [compacted]: ConvergentStore::compact
+/// [baselines]: ConvergentStore::baseline
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Baseline<S> {
+    /// The latest [`Stamp`] the state accounts for.
+    pub frontier: Stamp,
+
+    /// The state.
+    pub state: S,
+}
+
+/// A reactive state container that converges with other replicas (requires [`alloc`]).
+///
+/// [`ConvergentStore`] reduces actions in the order of their [`Stamp`]s
+/// rather than the order they are dispatched, so replicas that eventually dispatch the same
+/// set of [`Stamped`] actions converge to the same state, no matter the order actions are
+/// delivered in, nor whether they are delivered more than once.
+///
+/// Local actions are [stamped] before being dispatched locally and sent to other replicas.
+/// When an action arrives that precedes some of those already reduced,
+/// the state is recomputed by replaying the log of actions from the closest checkpoint,
+/// a copy of the state taken periodically,
+/// so the [`Reactor`] is only ever notified of states consistent with the total order.
+///
+/// The log grows with every action, until its stable prefix, the actions no replica will
+/// ever precede, is [compacted] into the _baseline_, the state all actions in the log
+/// are reduced on top of.
+/// If the state is [`Mergeable`], the [`Baseline`]s of other replicas may be dispatched too,
+/// which are [joined](Mergeable::join) with the local one.
+///
+/// Replaying actions is only meaningful if the [`Reducer`] is deterministic.
+///
+/// [`alloc`]: index.html#optional-features
+/// [stamped]: ConvergentStore::stamp
+/// [compacted]: ConvergentStore::compact
+///
+/// # Example
+///
+/// ```rust
+/// use reducer::*;
+/// use std::convert::Infallible;
+///
+/// #[derive(Debug, Default, Clone, PartialEq)]
+/// struct Document(String);
+///
+/// #[derive(Clone)]
+/// struct Append(&'static str);
+///
+/// impl Reducer<Append> for Document {
+///     fn reduce(&mut self, Append(text): Append) {
+///         self.0 += text;
+///     }
+/// }
+///
+/// struct Nop;
+///
+/// impl<S> Reactor<S> for Nop {
+///     type Error = Infallible;
+///     fn react(&mut self, _: &S) -> Result<(), Self::Error> {
+///         Ok(())
+///     }
+/// }
+///
+/// let mut laptop = ConvergentStore::new(0, Document::default(), Nop);
+/// let mut phone = ConvergentStore::new(1, Document::default(), Nop);
+///
+/// // Both devices edit the document while offline.
+/// let a = laptop.stamp(Append("Hello"));
+/// laptop.dispatch(a.clone()).unwrap();
+///
+/// let b = phone.stamp(Append(", world!"));
+/// phone.dispatch(b.clone()).unwrap();
+///
+/// // Once back online, they exchange their actions.
+/// laptop.dispatch(b.clone()).unwrap();
+/// phone.dispatch(a).unwrap();
+///
+/// assert_eq!(*laptop, *phone);
+/// assert_eq!(laptop.0, "Hello, world!");
+///
+/// // Both have seen all actions up to `b`, which may be compacted.
+/// laptop.compact(b.stamp);
+/// phone.compact(b.stamp);
+///
+/// assert!(laptop.log().is_empty());
+/// assert_eq!(laptop.baseline().state, *phone);
+/// ```
+#[derive(Debug)]
+pub struct ConvergentStore<A, S, R> {
+    store: Store<S, R>,
+    log: Vec<Stamped<A>>,
+    checkpoints: Vec<S>,
+    interval: usize,
+    frontier: Stamp,
+    replica: u64,
+    clock: u64,
+}
+
+impl<A, S, R> Deref for ConvergentStore<A, S, R> {
+    type Target = S;
+
+    fn deref(&self) -> &S {
+        &self.store
+    }
+}
+
+impl<A, S: Clone, R> ConvergentStore<A, S, R> {
+    /// Constructs the ConvergentStore given the id of this replica,
+    /// the initial state and a [`Reactor`].
+    ///
+    /// Every replica must start from the same initial state.
+    pub fn new(replica: u64, state: S, reactor: R) -> Self {
+        ConvergentStore {
+            checkpoints: alloc::vec![state.clone()],
+            store: Store::new(state, reactor),
+            log: Vec::new(),
+            interval: INTERVAL,
+            frontier: Stamp::default(),
+            replica,
+            clock: 0,
+        }
+    }
+
+    /// The [`Baseline`] every action in the log is reduced on top of.
+    pub fn baseline(&self) -> Baseline<S> {
+        Baseline {
+            frontier: self.frontier,
+            state: self.checkpoints[0].clone(),
+        }
+    }
+}
+
+impl<A, S, R> ConvergentStore<A, S, R> {
+    /// The id of this replica.
+    pub fn replica(&self) -> u64 {
+        self.replica
+    }
+
+    /// The current logical time of this replica.
+    pub fn clock(&self) -> u64 {
+        self.clock
+    }
+
+    /// The actions reduced on top of the [`Baseline`], in the order of their [`Stamp`]s.
+    ///
+    /// Replicas that join late may catch up by dispatching the log of another replica,
+    /// as well as its [baseline](ConvergentStore::baseline) if the log has been compacted.
+    pub fn log(&self) -> &[Stamped<A>] {
+        &self.log
+    }
+
+    /// Replaces the [`Reactor`] and returns the previous one.
+    pub fn subscribe(&mut self, reactor: impl Into<R>) -> R {
+        self.store.subscribe(reactor)
+    }
+
+    /// Tags a local action with a [`Stamp`] that succeeds all actions seen so far.
+    ///
+    /// The action still needs to be dispatched.
+    pub fn stamp(&mut self, action: A) -> Stamped<A> {
+        self.clock += 1;
+
+        Stamped {
+            stamp: Stamp {
+                time: self.clock,
+                replica: self.replica,
+            },
+            action,
+        }
+    }
+}
+
+impl<A, S, R> ConvergentStore<A, S, R>
+where
+    A: Clone,
+    S: Reducer<A> + Clone,
+{
+    /// Sets the number of actions between checkpoints, trading memory for replay time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval` is zero.
+    pub fn with_interval(mut self, interval: usize) -> Self {
+        assert_ne!(
+            interval, 0,
+            "the interval between checkpoints must not be zero"
+        );
+        self.checkpoints.truncate(1);
+        self.interval = interval;
+        self.replay();
+        self
+    }
+
+    /// Folds all actions up to and including `until` into the [`Baseline`]
+    /// and drops them from the log.
+    ///
+    /// Actions that don't succeed the [`Baseline`] are ignored from then on,
+    /// so only compact up to a [`Stamp`] every replica has seen all actions up to,
+    /// e.g. the earliest of the latest [`Stamp`]s each replica acknowledged.
+    pub fn compact(&mut self, until: Stamp) {
+        if until <= self.frontier {
+            return;
+        }
+
+        let len = self.log.partition_point(|s| s.stamp <= until);
+        let checkpoint = len / self.interval;
+
+        let mut baseline = self.checkpoints.swap_remove(checkpoint);
+        for stamped in &self.log[checkpoint * self.interval..len] {
+            baseline.reduce(stamped.action.clone());
+        }
+
+        self.log.drain(..len);
+        self.checkpoints.clear();
+        self.checkpoints.push(baseline);
+        self.frontier = until;
+        self.replay();
+    }
+
+    /// Recomputes the state and the missing checkpoints from the latest checkpoint.
+    fn replay(&mut self) {
+        let checkpoint = self.checkpoints.len() - 1;
+        let (state, _) = self.store.parts_mut();
+        *state = self.checkpoints[checkpoint].clone();
+
+        for (i, stamped) in self.log.iter().enumerate().skip(checkpoint * self.interval) {
+            state.reduce(stamped.action.clone());
+
+            if self.checkpoints.len() * self.interval == i + 1 {
+                self.checkpoints.push(state.clone());
+            }
+        }
+    }
+}
+
+impl<A, S, R> Dispatcher<Stamped<A>> for ConvergentStore<A, S, R>
+where
+    A: Clone,
+    S: Reducer<A> + Clone,
+    R: Reactor<S>,
+{
+    type Output = Result<(), R::Error>;
+
+    /// Records the action in the order of its [`Stamp`], then either updates the state via
+    /// [`Reducer::reduce`] or recomputes it, and notifies the [`Reactor`].
+    ///
+    /// Actions that have already been dispatched or compacted are ignored.
+    fn dispatch(&mut self, stamped: Stamped<A>) -> Self::Output {
+        self.clock = self.clock.max(stamped.stamp.time);
+
+        if stamped.stamp <= self.frontier {
+            return Ok(());
+        }
+
+        let index = match self.log.binary_search_by_key(&stamped.stamp, |s| s.stamp) {
+            Ok(_) => return Ok(()),
+            Err(index) => index,
+        };
+
+        if index == self.log.len() {
+            self.log.push(stamped.clone());
+            let result = self.store.dispatch(stamped.action);
+
+            if self.checkpoints.len() * self.interval == self.log.len() {
+                self.checkpoints.push(S::clone(&self.store));
+            }
+
+            return result;
+        }
+
+        self.log.insert(index, stamped);
+        self.checkpoints.truncate(index / self.interval + 1);
+        self.replay();
+
+        let (state, reactor) = self.store.parts_mut();
+        reactor.react(state)
+    }
+}
+
+impl<A, S, R> Dispatcher<Baseline<S>> for ConvergentStore<A, S, R>
+where
+    A: Clone,
+    S: Reducer<A> + Mergeable + Clone,
+    R: Reactor<S>,
+{
+    type Output = Result<(), R::Error>;
+
+    /// [Joins](Mergeable::join) the [`Baseline`] of another replica with the local one,
+    /// drops the actions it accounts for from the log, then recomputes the state
+    /// and notifies the [`Reactor`].
+    fn dispatch(&mut self, baseline: Baseline<S>) -> Self::Output {
+        self.clock = self.clock.max(baseline.frontier.time);
+
+        let len = self.log.partition_point(|s| s.stamp <= baseline.frontier);
+        self.log.drain(..len);
+        self.frontier = self.frontier.max(baseline.frontier);
+
+        self.checkpoints.truncate(1);
+        self.checkpoints[0].join(baseline.state);
+        self.replay();
+
+        let (state, reactor) = self.store.parts_mut();
+        reactor.react(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reactor::MockReactor;
+    use alloc::collections::BTreeSet;
+    use proptest::sample::Index;
+    use test_strategy::proptest;
+
+    #[derive(Debug, Default, Clone, Eq, PartialEq)]
+    struct History(Vec<u8>);
+
+    impl Reducer<u8> for History {
+        fn reduce(&mut self, action: u8) {
+            self.0.push(action);
+        }
+    }
+
+    /// Stamps actions as if each of two replicas dispatched them locally in turn.
+    fn stamped(actions: &[(bool, u8)]) -> Vec<Stamped<u8>> {
+        let mut clocks = [0, 0];
+
+        actions
+            .iter()
+            .map(|&(replica, action)| {
+                let time = &mut clocks[usize::from(replica)];
+                *time += 1;
+
+                Stamped {
+                    stamp: Stamp {
+                        time: *time,
+                        replica: replica.into(),
+                    },
+                    action,
+                }
+            })
+            .collect()
+    }
+
+    #[proptest]
+    fn stamp(replica: u64, actions: Vec<u8>) {
+        let reactor = MockReactor::<History, ()>::new();
+        let mut store = ConvergentStore::new(replica, History::default(), reactor);
+        assert_eq!(store.replica(), replica);
+
+        for (i, &action) in actions.iter().enumerate() {
+            let stamped = store.stamp(action);
+            assert_eq!(stamped.action, action);
+            assert_eq!(stamped.stamp.replica, replica);
+            assert_eq!(stamped.stamp.time, i as u64 + 1);
+            assert_eq!(store.clock(), i as u64 + 1);
+        }
+    }
+
+    #[derive(Debug, Default, Clone, Eq, PartialEq)]
+    struct Set(BTreeSet<u8>);
+
+    impl Reducer<u8> for Set {
+        fn reduce(&mut self, action: u8) {
+            self.0.insert(action);
+        }
+    }
+
+    impl Mergeable for Set {
+        fn join(&mut self, other: Self) {
+            self.0.join(other.0);
+        }
+    }
+
+    #[proptest]
+    fn converge(
+        state: Vec<u8>,
+        actions: Vec<(bool, u8)>,
+        shuffle: Vec<Index>,
+        #[strategy(1usize..8)] interval: usize,
+    ) {
+        let actions = stamped(&actions);
+
+        let mut delivered = actions.clone();
+        for (i, j) in shuffle.iter().enumerate() {
+            if !delivered.is_empty() {
+                let len = delivered.len();
+                delivered.swap(i % len, j.index(len));
+            }
+        }
+
+        // Duplicates are ignored.
+        delivered.extend(actions.iter().take(shuffle.len()).cloned());
+
+        let mut reactor = MockReactor::new();
+        reactor
+            .expect_react()
+            .times(actions.len())
+            .return_const(Ok::<_, ()>(()));
+
+        let mut store =
+            ConvergentStore::new(2, History(state.clone()), reactor).with_interval(interval);
+        let time = actions.iter().map(|s| s.stamp.time).max().unwrap_or(0);
+
+        for stamped in delivered {
+            assert_eq!(store.dispatch(stamped), Ok(()));
+            assert_eq!(store.checkpoints.len(), store.log.len() / interval + 1);
+        }
+
+        let mut sorted = actions;
+        sorted.sort_by_key(|s| s.stamp);
+
+        let mut expected = state;
+        expected.extend(sorted.iter().map(|s| s.action));
+
+        assert_eq!(*store, History(expected));
+        assert_eq!(store.log(), &sorted[..]);
+        assert_eq!(store.clock(), time);
+        assert!(store.stamp(0).stamp > sorted.last().map_or(Stamp::default(), |s| s.stamp));
+    }
+
+    #[proptest]
+    fn compact(
+        actions: Vec<(bool, u8)>,
+        until: Index,
+        late: Index,
+        #[strategy(1usize..8)] interval: usize,
+    ) {
+        let mut sorted = stamped(&actions);
+        sorted.sort_by_key(|s| s.stamp);
+
+        let mut reactor = MockReactor::new();
+        reactor
+            .expect_react()
+            .times(sorted.len())
+            .return_const(Ok::<_, ()>(()));
+
+        let mut store =
+            ConvergentStore::new(2, History::default(), reactor).with_interval(interval);
+
+        for &stamped in &sorted {
+            assert_eq!(store.dispatch(stamped), Ok(()));
+        }
+
+        let len = until.index(sorted.len() + 1);
+        let until = len
+            .checked_sub(1)
+            .map_or(Stamp::default(), |i| sorted[i].stamp);
+        let expected = History(sorted.iter().map(|s| s.action).collect());
+
+        store.compact(until);
+        assert_eq!(*store, expected);
+        assert_eq!(store.log(), &sorted[len..]);
+        assert_eq!(store.checkpoints.len(), store.log.len() / interval + 1);
+
+        let baseline = store.baseline();
+        assert_eq!(baseline.frontier, until);
+        assert_eq!(baseline.state, History(expected.0[..len].into()));
+
+        // Compacted actions are ignored.
+        if len > 0 {
+            assert_eq!(store.dispatch(sorted[late.index(len)]), Ok(()));
+            assert_eq!(*store, expected);
+        }
+    }
+
+    #[proptest]
+    fn baseline(actions: Vec<(bool, u8)>, until: Index, local: Vec<u8>) {
+        let actions = stamped(&actions);
+
+        let mut reactor = MockReactor::new();
+        reactor.expect_react().return_const(Ok::<_, ()>(()));
+
+        let mut remote = ConvergentStore::new(2, Set::default(), reactor);
+
+        for &stamped in &actions {
+            assert_eq!(remote.dispatch(stamped), Ok(()));
+        }
+
+        let mut sorted = actions;
+        sorted.sort_by_key(|s| s.stamp);
+
+        if let Some(stamped) = sorted.get(until.index(sorted.len() + 1)) {
+            remote.compact(stamped.stamp);
+        }
+
+        let mut reactor = MockReactor::new();
+        reactor.expect_react().return_const(Ok::<_, ()>(()));
+
+        // A late replica catches up on the baseline and the log.
+        let mut store = ConvergentStore::new(3, Set::default(), reactor);
+        assert_eq!(store.dispatch(remote.baseline()), Ok(()));
+
+        for &stamped in remote.log() {
+            assert_eq!(store.dispatch(stamped), Ok(()));
+        }
+
+        assert_eq!(*store, *remote);
+
+        // Then edits the state locally.
+        for &action in &local {
+            let stamped = store.stamp(action);
+            assert!(stamped.stamp > remote.baseline().frontier);
+            assert_eq!(store.dispatch(stamped), Ok(()));
+        }
+
+        let mut expected = remote.0.clone();
+        expected.extend(local.iter().copied());
+
+        assert_eq!(store.0, expected);
+    }
+
+    #[proptest]
+    fn error(actions: [(bool, u8); 2], results: [Result<(), u8>; 2]) {
+        let mut reactor = MockReactor::new();
+
+        for result in results {
+            reactor.expect_react().once().return_const(result);
+        }
+
+        let actions = stamped(&actions);
+        let mut store = ConvergentStore::new(2, History::default(), reactor);
+
+        assert_eq!(store.dispatch(actions[1]), results[0]);
+        assert_eq!(store.dispatch(actions[0]), results[1]);
+    }
+}
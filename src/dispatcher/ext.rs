@@ -0,0 +1,525 @@
+use crate::dispatcher::Dispatcher;
+use derive_more::Deref;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// Extension methods for [`Dispatcher`]s.
+///
+/// Adapters wrap the underlying [`Dispatcher`] and dereference to it,
+/// so e.g. the state of an adapted [`Store`](crate::Store) remains accessible.
+///
+/// To dispatch every action to several [`Dispatcher`]s, chain them with [`DispatcherExt::and`],
+/// or group them in a tuple, an array, a slice or a `Vec`,
+/// all of which implement [`Dispatcher`] themselves.
+///
+/// # Example
+///
+/// ```rust
+/// use reducer::*;
+///
+/// #[derive(Default)]
+/// struct Form {
+///     name: String,
+///     submitted: bool,
+/// }
+///
+/// // The actions the form widget knows about.
+/// enum Input {
+///     Type(char),
+///     Submit,
+/// }
+///
+/// // The actions the application knows about.
+/// enum Action {
+///     Form(Input),
+///     // ...
+/// }
+///
+/// impl Reducer<Action> for Form {
+///     fn reduce(&mut self, action: Action) {
+///         match action {
+///             Action::Form(Input::Type(c)) => self.name.push(c),
+///             Action::Form(Input::Submit) => self.submitted = true,
+///         }
+///     }
+/// }
+///
+/// struct Nop;
+///
+/// impl<S> Reactor<S> for Nop {
+///     type Error = std::convert::Infallible;
+///     fn react(&mut self, _: &S) -> Result<(), Self::Error> {
+///         Ok(())
+///     }
+/// }
+///
+/// // The widget is agnostic to the application's action type.
+/// fn fill<D: Dispatcher<Input>>(form: &mut D, name: &str) {
+///     name.chars().for_each(|c| {
+///         form.dispatch(Input::Type(c));
+///     });
+///
+///     form.dispatch(Input::Submit);
+/// }
+///
+/// let store = Store::new(Form::default(), Nop);
+///
+/// let mut form = store
+///     .filter(|action: &Action| !matches!(action, Action::Form(Input::Type(' '))))
+///     .contramap(Action::Form);
+///
+/// fill(&mut form, "Jane Doe");
+///
+/// assert_eq!(form.name, "JaneDoe");
+/// assert!(form.submitted);
+/// ```
+pub trait DispatcherExt<A>: Dispatcher<A> {
+    /// Adapts the [`Dispatcher`] to a different action type, by mapping actions through `f`
+    /// before dispatching them.
+    fn contramap<B, F>(self, f: F) -> Contramap<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(B) -> A,
+    {
+        Contramap {
+            dispatcher: self,
+            f,
+        }
+    }
+
+    /// Only dispatches actions for which `predicate` returns `true`.
+    fn filter<P>(self, predicate: P) -> Filter<Self, P>
+    where
+        Self: Sized,
+        P: FnMut(&A) -> bool,
+    {
+        Filter {
+            dispatcher: self,
+            predicate,
+        }
+    }
+
+    /// Calls `f` with a reference to every action before dispatching it.
+    fn inspect<F>(self, f: F) -> Inspect<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(&A),
+    {
+        Inspect {
+            dispatcher: self,
+            f,
+        }
+    }
+
+    /// Dispatches every action to both this and the `other` [`Dispatcher`], in order.
+    fn and<D>(self, other: D) -> Fanout<Self, D>
+    where
+        Self: Sized,
+        D: Dispatcher<A>,
+        A: Clone,
+    {
+        Fanout {
+            first: self,
+            second: other,
+        }
+    }
+
+    /// Merges consecutive actions dispatched in [batches](Coalesce::dispatch_batch)
+    /// through `merge`, which returns the action equivalent to dispatching both,
+    /// or hands them back if they can't be merged.
+    fn coalesce<F>(self, merge: F) -> Coalesce<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(A, A) -> Result<A, (A, A)>,
+    {
+        Coalesce {
+            dispatcher: self,
+            merge,
+        }
+    }
+}
+
+impl<A, D: Dispatcher<A>> DispatcherExt<A> for D {}
+
+/// The [`Dispatcher`] returned by [`DispatcherExt::contramap`].
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Deref)]
+pub struct Contramap<D, F> {
+    #[deref]
+    dispatcher: D,
+    f: F,
+}
+
+impl<D, F> Contramap<D, F> {
+    /// Returns the underlying [`Dispatcher`].
+    pub fn into_inner(self) -> D {
+        self.dispatcher
+    }
+}
+
+impl<A, B, D, F> Dispatcher<B> for Contramap<D, F>
+where
+    D: Dispatcher<A>,
+    F: FnMut(B) -> A,
+{
+    type Output = D::Output;
+
+    /// Maps the action through `f` and then dispatches it.
+    fn dispatch(&mut self, action: B) -> Self::Output {
+        self.dispatcher.dispatch((self.f)(action))
+    }
+}
+
+/// The [`Dispatcher`] returned by [`DispatcherExt::filter`].
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Deref)]
+pub struct Filter<D, P> {
+    #[deref]
+    dispatcher: D,
+    predicate: P,
+}
+
+impl<D, P> Filter<D, P> {
+    /// Returns the underlying [`Dispatcher`].
+    pub fn into_inner(self) -> D {
+        self.dispatcher
+    }
+}
+
+impl<A, D, P> Dispatcher<A> for Filter<D, P>
+where
+    D: Dispatcher<A>,
+    P: FnMut(&A) -> bool,
+{
+    type Output = Option<D::Output>;
+
+    /// Dispatches the action if it satisfies the predicate, otherwise returns `None`.
+    fn dispatch(&mut self, action: A) -> Self::Output {
+        if (self.predicate)(&action) {
+            Some(self.dispatcher.dispatch(action))
+        } else {
+            None
+        }
+    }
+}
+
+/// The [`Dispatcher`] returned by [`DispatcherExt::inspect`].
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Deref)]
+pub struct Inspect<D, F> {
+    #[deref]
+    dispatcher: D,
+    f: F,
+}
+
+impl<D, F> Inspect<D, F> {
+    /// Returns the underlying [`Dispatcher`].
+    pub fn into_inner(self) -> D {
+        self.dispatcher
+    }
+}
+
+impl<A, D, F> Dispatcher<A> for Inspect<D, F>
+where
+    D: Dispatcher<A>,
+    F: FnMut(&A),
+{
+    type Output = D::Output;
+
+    /// Calls `f` with a reference to the action and then dispatches it.
+    fn dispatch(&mut self, action: A) -> Self::Output {
+        (self.f)(&action);
+        self.dispatcher.dispatch(action)
+    }
+}
+
+/// The [`Dispatcher`] returned by [`DispatcherExt::and`].
+///
+/// # Example
+///
+/// ```rust
+/// use reducer::*;
+///
+/// #[derive(Debug, Default, PartialEq)]
+/// struct Clicks(u32);
+///
+/// #[derive(Clone)]
+/// struct Click;
+///
+/// impl Reducer<Click> for Clicks {
+///     fn reduce(&mut self, _: Click) {
+///         self.0 += 1;
+///     }
+/// }
+///
+/// struct Nop;
+///
+/// impl<S> Reactor<S> for Nop {
+///     type Error = std::convert::Infallible;
+///     fn react(&mut self, _: &S) -> Result<(), Self::Error> {
+///         Ok(())
+///     }
+/// }
+///
+/// let session = Store::new(Clicks::default(), Nop);
+/// let lifetime = Store::new(Clicks(41), Nop);
+///
+/// let mut clicks = session.and(lifetime);
+/// clicks.dispatch(Click);
+///
+/// let (session, lifetime) = clicks.into_inner();
+/// assert_eq!(*session, Clicks(1));
+/// assert_eq!(*lifetime, Clicks(42));
+/// ```
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Fanout<D, E> {
+    first: D,
+    second: E,
+}
+
+impl<D, E> Fanout<D, E> {
+    /// Returns the underlying [`Dispatcher`]s.
+    pub fn into_inner(self) -> (D, E) {
+        (self.first, self.second)
+    }
+}
+
+impl<A, D, E> Dispatcher<A> for Fanout<D, E>
+where
+    A: Clone,
+    D: Dispatcher<A>,
+    E: Dispatcher<A>,
+{
+    type Output = (D::Output, E::Output);
+
+    /// Dispatches a clone of the action to the first [`Dispatcher`]
+    /// and then the action itself to the second one.
+    fn dispatch(&mut self, action: A) -> Self::Output {
+        (
+            self.first.dispatch(action.clone()),
+            self.second.dispatch(action),
+        )
+    }
+}
+
+/// The [`Dispatcher`] returned by [`DispatcherExt::coalesce`].
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Deref)]
+pub struct Coalesce<D, F> {
+    #[deref]
+    dispatcher: D,
+    merge: F,
+}
+
+impl<D, F> Coalesce<D, F> {
+    /// Returns the underlying [`Dispatcher`].
+    pub fn into_inner(self) -> D {
+        self.dispatcher
+    }
+
+    /// Merges consecutive actions as far as possible and dispatches the result in order,
+    /// collecting the outputs (requires [`alloc`]).
+    ///
+    /// [`alloc`]: index.html#optional-features
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use reducer::*;
+    ///
+    /// #[derive(Debug, Default)]
+    /// struct Todo {
+    ///     text: String,
+    ///     done: bool,
+    /// }
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// enum Action {
+    ///     Edit(String),
+    ///     Toggle,
+    /// }
+    ///
+    /// impl Reducer<Action> for Todo {
+    ///     fn reduce(&mut self, action: Action) {
+    ///         match action {
+    ///             Action::Edit(text) => self.text = text,
+    ///             Action::Toggle => self.done = !self.done,
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// struct Nop;
+    ///
+    /// impl<S> Reactor<S> for Nop {
+    ///     type Error = std::convert::Infallible;
+    ///     fn react(&mut self, _: &S) -> Result<(), Self::Error> {
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// let merge = |a, b| match (a, b) {
+    ///     // The last edit wins.
+    ///     (Action::Edit(_), Action::Edit(text)) => Ok(Action::Edit(text)),
+    ///     pair => Err(pair),
+    /// };
+    ///
+    /// let mut todo = Store::new(Todo::default(), Nop).coalesce(merge);
+    ///
+    /// let keystrokes = ["b", "bu", "buy", "buy m", "buy milk"];
+    /// let actions = keystrokes.iter().map(|&text| Action::Edit(text.into()));
+    ///
+    /// // The reactor is only notified once.
+    /// assert_eq!(todo.dispatch_batch(actions).len(), 1);
+    /// assert_eq!(todo.text, "buy milk");
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn dispatch_batch<A, I>(&mut self, actions: I) -> Vec<D::Output>
+    where
+        D: Dispatcher<A>,
+        F: FnMut(A, A) -> Result<A, (A, A)>,
+        I: IntoIterator<Item = A>,
+    {
+        let mut outputs = Vec::new();
+        let mut pending = None;
+
+        for action in actions {
+            pending = match pending.take() {
+                None => Some(action),
+                Some(previous) => match (self.merge)(previous, action) {
+                    Ok(merged) => Some(merged),
+                    Err((previous, action)) => {
+                        outputs.push(self.dispatcher.dispatch(previous));
+                        Some(action)
+                    }
+                },
+            };
+        }
+
+        outputs.extend(pending.map(|action| self.dispatcher.dispatch(action)));
+        outputs
+    }
+}
+
+impl<A, D, F> Dispatcher<A> for Coalesce<D, F>
+where
+    D: Dispatcher<A>,
+{
+    type Output = D::Output;
+
+    /// Dispatches the action, since there is nothing to merge it with.
+    fn dispatch(&mut self, action: A) -> Self::Output {
+        self.dispatcher.dispatch(action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dispatcher::MockDispatcher;
+    use mockall::{predicate::*, Sequence};
+    use test_strategy::proptest;
+
+    #[proptest]
+    fn contramap(action: u8, result: u8) {
+        let mut mock = MockDispatcher::<u16, u8>::new();
+
+        mock.expect_dispatch()
+            .with(eq(u16::from(action) + 1))
+            .once()
+            .return_const(result);
+
+        let mut dispatcher = mock.contramap(|a: u8| u16::from(a) + 1);
+        assert_eq!(dispatcher.dispatch(action), result);
+    }
+
+    #[proptest]
+    fn filter(action: u8, result: u8, pass: bool) {
+        let mut mock = MockDispatcher::<u8, u8>::new();
+
+        mock.expect_dispatch()
+            .with(eq(action))
+            .times(usize::from(pass))
+            .return_const(result);
+
+        let mut dispatcher = mock.filter(|&a| {
+            assert_eq!(a, action);
+            pass
+        });
+
+        assert_eq!(dispatcher.dispatch(action), pass.then_some(result));
+    }
+
+    #[proptest]
+    fn inspect(action: u8, result: u8) {
+        let mut mock = MockDispatcher::<u8, u8>::new();
+        let mut inspected = None;
+
+        mock.expect_dispatch()
+            .with(eq(action))
+            .once()
+            .return_const(result);
+
+        let mut dispatcher = mock.inspect(|&a| inspected = Some(a));
+        assert_eq!(dispatcher.dispatch(action), result);
+
+        drop(dispatcher);
+        assert_eq!(inspected, Some(action));
+    }
+
+    #[proptest]
+    fn and(action: u8, a: u8, b: u8) {
+        let mut seq = Sequence::new();
+        let mut first = MockDispatcher::<u8, u8>::new();
+        let mut second = MockDispatcher::<u8, u8>::new();
+
+        first
+            .expect_dispatch()
+            .with(eq(action))
+            .once()
+            .in_sequence(&mut seq)
+            .return_const(a);
+
+        second
+            .expect_dispatch()
+            .with(eq(action))
+            .once()
+            .in_sequence(&mut seq)
+            .return_const(b);
+
+        let mut dispatcher = first.and(second);
+        assert_eq!(dispatcher.dispatch(action), (a, b));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[proptest]
+    fn coalesce(actions: Vec<u8>) {
+        // Merges runs of equal parity by adding them up.
+        let merge = |a: u8, b: u8| {
+            if a % 2 == b % 2 {
+                Ok(a.wrapping_add(b))
+            } else {
+                Err((a, b))
+            }
+        };
+
+        let mut expected: Vec<u8> = Vec::new();
+        for &action in &actions {
+            match expected.last_mut() {
+                Some(last) if *last % 2 == action % 2 => *last = last.wrapping_add(action),
+                _ => expected.push(action),
+            }
+        }
+
+        let mut seq = Sequence::new();
+        let mut mock = MockDispatcher::<u8, u8>::new();
+
+        for &action in &expected {
+            mock.expect_dispatch()
+                .with(eq(action))
+                .once()
+                .in_sequence(&mut seq)
+                .return_const(action);
+        }
+
+        let mut dispatcher = mock.coalesce(merge);
+        assert_eq!(dispatcher.dispatch_batch(actions), expected);
+        assert_eq!(dispatcher.dispatch_batch(None), Vec::<u8>::new());
+    }
+}
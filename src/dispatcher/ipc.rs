@@ -0,0 +1,466 @@
+use crate::dispatcher::Dispatcher;
+use crate::trace;
+use core::convert::TryFrom;
+use serde::{de::DeserializeOwned, Serialize};
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::vec::Vec;
+
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+
+/// The default maximum length of the frames an [`IpcServer`] accepts.
+const MAX_FRAME: usize = 1 << 20;
+
+/// Trait for types that (de)serialize actions sent across processes (requires [`ipc`]).
+///
+/// [`JsonCodec`] is provided, binary formats such as [bincode] or [postcard]
+/// can be plugged in by implementing this trait.
+///
+/// [`ipc`]: index.html#optional-features
+/// [bincode]: https://crates.io/crates/bincode
+/// [postcard]: https://crates.io/crates/postcard
+pub trait Codec<A> {
+    /// Serializes an action.
+    fn encode(&self, action: &A) -> io::Result<Vec<u8>>;
+
+    /// Deserializes an action.
+    fn decode(&self, bytes: &[u8]) -> io::Result<A>;
+}
+
+/// A [`Codec`] that (de)serializes actions as JSON (requires [`ipc`]).
+///
+/// [`ipc`]: index.html#optional-features
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct JsonCodec;
+
+impl<A: Serialize + DeserializeOwned> Codec<A> for JsonCodec {
+    fn encode(&self, action: &A) -> io::Result<Vec<u8>> {
+        Ok(serde_json::to_vec(action)?)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> io::Result<A> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
+/// Trait for listeners an [`IpcServer`] accepts connections from (requires [`ipc`]).
+///
+/// [`ipc`]: index.html#optional-features
+pub trait IpcListener {
+    /// The connection to a client.
+    type Stream: Read + Send + 'static;
+
+    /// Blocks until a client connects.
+    fn accept(&self) -> io::Result<Self::Stream>;
+}
+
+impl IpcListener for TcpListener {
+    type Stream = TcpStream;
+
+    fn accept(&self) -> io::Result<Self::Stream> {
+        Ok(TcpListener::accept(self)?.0)
+    }
+}
+
+#[cfg(unix)]
+impl IpcListener for UnixListener {
+    type Stream = UnixStream;
+
+    fn accept(&self) -> io::Result<Self::Stream> {
+        Ok(UnixListener::accept(self)?.0)
+    }
+}
+
+/// Prefixes the payload with its length.
+fn frame(payload: Vec<u8>) -> io::Result<Vec<u8>> {
+    let len = u32::try_from(payload.len())
+        .map_err(|_| io::Error::new(ErrorKind::InvalidInput, "action too large"))?;
+
+    let mut frame = Vec::with_capacity(payload.len() + 4);
+    frame.extend_from_slice(&len.to_be_bytes());
+    frame.extend(payload);
+    Ok(frame)
+}
+
+/// Reads the next payload, or `None` if the stream is closed between frames.
+///
+/// Frames longer than `max` are rejected before their payload is allocated.
+fn read<T: Read>(stream: &mut T, max: usize) -> io::Result<Option<Vec<u8>>> {
+    let mut len = [0; 4];
+
+    match stream.read_exact(&mut len) {
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        result => result?,
+    }
+
+    let len = u32::from_be_bytes(len) as usize;
+
+    if len > max {
+        return Err(io::Error::new(ErrorKind::InvalidData, "frame too large"));
+    }
+
+    let mut payload = std::vec![0; len];
+    stream.read_exact(&mut payload)?;
+    Ok(Some(payload))
+}
+
+/// A [`Dispatcher`] that sends actions to an [`IpcServer`] in another process
+/// (requires [`ipc`]).
+///
+/// Actions are serialized by a [`Codec`] and written to a stream,
+/// typically a [`TcpStream`] or a [`UnixStream`](std::os::unix::net::UnixStream),
+/// as frames prefixed by their length.
+///
+/// Writes block the current thread, so [`IpcDispatcher`] only implements [`Dispatcher`],
+/// from within an asynchronous context it's best used on a blocking thread.
+///
+/// [`ipc`]: index.html#optional-features
+///
+/// # Example
+///
+/// ```rust
+/// use reducer::*;
+/// use futures::channel::mpsc::channel;
+/// use futures::prelude::*;
+/// use serde::{Deserialize, Serialize};
+/// use std::error::Error;
+/// use std::net::{TcpListener, TcpStream};
+/// use std::thread;
+/// use tokio::task::spawn;
+///
+/// #[derive(Debug, Clone, Eq, PartialEq)]
+/// struct Counter(i32);
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Add(i32);
+///
+/// impl Reducer<Add> for Counter {
+///     fn reduce(&mut self, Add(x): Add) {
+///         self.0 += x;
+///     }
+/// }
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn Error>> {
+///     let (tx, mut rx) = channel(0);
+///     let (task, dispatcher) = Store::new(Counter(0), AsyncReactor(tx)).into_task();
+///     spawn(task);
+///
+///     // The daemon feeds actions received from clients into its store.
+///     let listener = TcpListener::bind("127.0.0.1:0")?;
+///     let addr = listener.local_addr()?;
+///     thread::spawn(move || IpcServer::new(listener, JsonCodec, dispatcher).serve::<Add, _>());
+///
+///     // Clients, typically other processes, dispatch actions remotely.
+///     let mut client = IpcDispatcher::new(TcpStream::connect(addr)?, JsonCodec);
+///     client.dispatch(Add(2))?;
+///     client.dispatch(Add(3))?;
+///
+///     assert_eq!(rx.next().await, Some(Counter(2)));
+///     assert_eq!(rx.next().await, Some(Counter(5)));
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct IpcDispatcher<C, T> {
+    stream: T,
+    codec: C,
+}
+
+impl<C, T> IpcDispatcher<C, T> {
+    /// Constructs the IpcDispatcher given the stream to a server and a [`Codec`].
+    pub fn new(stream: T, codec: C) -> Self {
+        IpcDispatcher { stream, codec }
+    }
+
+    /// Returns the stream to the server.
+    pub fn into_inner(self) -> T {
+        self.stream
+    }
+
+    fn send<A>(&mut self, action: &A) -> io::Result<()>
+    where
+        C: Codec<A>,
+        T: Write,
+    {
+        self.stream.write_all(&frame(self.codec.encode(action)?)?)
+    }
+}
+
+impl<A, C, T> Dispatcher<A> for IpcDispatcher<C, T>
+where
+    C: Codec<A>,
+    T: Write,
+{
+    /// Either confirmation that action has been sent or the reason why not.
+    type Output = io::Result<()>;
+
+    /// Serializes the action and sends it to the server.
+    ///
+    /// Once this call returns, the action may or may not have taken effect,
+    /// but it's guaranteed to eventually do,
+    /// unless the server shuts down in between.
+    fn dispatch(&mut self, action: A) -> Self::Output {
+        self.send(&action)?;
+        self.stream.flush()
+    }
+}
+
+/// Feeds actions sent by [`IpcDispatcher`]s into a local [`Dispatcher`] (requires [`ipc`]).
+///
+/// The [`Dispatcher`] is typically the [`TaskDispatcher`](crate::TaskDispatcher)
+/// returned by [`Store::into_task`](crate::Store::into_task).
+/// Every connection is served on its own thread by a clone of the [`Dispatcher`],
+/// so actions sent by the same client are dispatched in order.
+///
+/// Connections are closed if a frame is longer than the [maximum], an action can't be decoded
+/// or the [`Dispatcher`] fails.
+/// Since connections are served in the background, their errors are not returned,
+/// but recorded as [`tracing`] events if the feature is enabled.
+///
+/// See [`IpcDispatcher`] for an example.
+///
+/// [`ipc`]: index.html#optional-features
+/// [`tracing`]: index.html#optional-features
+/// [maximum]: IpcServer::with_max_frame
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct IpcServer<L, C, D> {
+    listener: L,
+    codec: C,
+    dispatcher: D,
+    max_frame: usize,
+}
+
+impl<L: Default, C: Default, D: Default> Default for IpcServer<L, C, D> {
+    fn default() -> Self {
+        Self::new(L::default(), C::default(), D::default())
+    }
+}
+
+impl<L, C, D> IpcServer<L, C, D> {
+    /// Constructs the IpcServer given an [`IpcListener`], a [`Codec`] and a [`Dispatcher`].
+    ///
+    /// Frames are limited to 1 MiB by default.
+    pub fn new(listener: L, codec: C, dispatcher: D) -> Self {
+        IpcServer {
+            listener,
+            codec,
+            dispatcher,
+            max_frame: MAX_FRAME,
+        }
+    }
+
+    /// Sets the maximum length in bytes of the encoded actions clients may send.
+    ///
+    /// Connections that send longer frames are closed with [`ErrorKind::InvalidData`],
+    /// which keeps clients from making the server allocate arbitrary amounts of memory.
+    pub fn with_max_frame(mut self, len: usize) -> Self {
+        self.max_frame = len;
+        self
+    }
+
+    /// Accepts connections until the [`IpcListener`] fails, blocking the current thread.
+    pub fn serve<A, E>(self) -> io::Result<()>
+    where
+        L: IpcListener,
+        C: Codec<A> + Clone + Send + 'static,
+        D: Dispatcher<A, Output = Result<(), E>> + Clone + Send + 'static,
+    {
+        loop {
+            let stream = self.listener.accept()?;
+            let codec = self.codec.clone();
+            let dispatcher = self.dispatcher.clone();
+            let max = self.max_frame;
+            thread::spawn(move || {
+                connect(stream, codec, dispatcher, max).inspect_err(trace::disconnected)
+            });
+        }
+    }
+}
+
+fn connect<A, E, T, C, D>(mut stream: T, codec: C, mut dispatcher: D, max: usize) -> io::Result<()>
+where
+    T: Read,
+    C: Codec<A>,
+    D: Dispatcher<A, Output = Result<(), E>>,
+{
+    while let Some(payload) = read(&mut stream, max)? {
+        if dispatcher.dispatch(codec.decode(&payload)?).is_err() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::{channel, Receiver, SendError, Sender};
+    use test_strategy::proptest;
+
+    #[derive(Debug, Clone)]
+    struct Forward(Sender<u8>);
+
+    impl Dispatcher<u8> for Forward {
+        type Output = Result<(), SendError<u8>>;
+
+        fn dispatch(&mut self, action: u8) -> Self::Output {
+            self.0.send(action)
+        }
+    }
+
+    /// A binary [`Codec`] that encodes every action as a single byte.
+    #[derive(Debug, Clone)]
+    struct Byte;
+
+    impl Codec<u8> for Byte {
+        fn encode(&self, &action: &u8) -> io::Result<Vec<u8>> {
+            Ok(std::vec![action])
+        }
+
+        fn decode(&self, bytes: &[u8]) -> io::Result<u8> {
+            match *bytes {
+                [action] => Ok(action),
+                _ => Err(ErrorKind::InvalidData.into()),
+            }
+        }
+    }
+
+    fn spawn<L, C>(server: IpcServer<L, C, ()>) -> Receiver<u8>
+    where
+        L: IpcListener + Send + 'static,
+        C: Codec<u8> + Clone + Send + 'static,
+    {
+        let (tx, rx) = channel();
+
+        let server = IpcServer {
+            listener: server.listener,
+            codec: server.codec,
+            dispatcher: Forward(tx),
+            max_frame: server.max_frame,
+        };
+
+        thread::spawn(move || server.serve::<u8, _>());
+        rx
+    }
+
+    #[proptest]
+    fn codec(action: Vec<u8>) {
+        let bytes = Codec::<Vec<u8>>::encode(&JsonCodec, &action)?;
+        let frame = frame(bytes.clone())?;
+        assert_eq!(read(&mut &frame[..], bytes.len())?, Some(bytes.clone()));
+        assert_eq!(Codec::<Vec<u8>>::decode(&JsonCodec, &bytes)?, action);
+        assert_eq!(read(&mut &frame[..frame.len() - 1], MAX_FRAME).ok(), None);
+        assert_eq!(read(&mut &[][..], MAX_FRAME)?, None);
+
+        assert_eq!(
+            read(&mut &frame[..], bytes.len() - 1).map_err(|e| e.kind()),
+            Err(ErrorKind::InvalidData)
+        );
+    }
+
+    #[proptest]
+    #[cfg_attr(miri, ignore)] // Sockets are not supported.
+    fn tcp(actions: Vec<u8>) {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let rx = spawn(IpcServer::new(listener, JsonCodec, ()));
+
+        let mut dispatcher = IpcDispatcher::new(TcpStream::connect(addr)?, JsonCodec);
+
+        for &action in &actions {
+            assert_eq!(dispatcher.dispatch(action).ok(), Some(()));
+        }
+
+        drop(dispatcher);
+        assert_eq!(rx.iter().take(actions.len()).collect::<Vec<_>>(), actions);
+    }
+
+    #[cfg(unix)]
+    #[proptest]
+    #[cfg_attr(miri, ignore)] // Sockets are not supported.
+    fn unix(actions: Vec<u8>) {
+        let path = std::env::temp_dir().join(std::format!(
+            "reducer-ipc-{}-{:?}.sock",
+            std::process::id(),
+            thread::current().id()
+        ));
+
+        drop(std::fs::remove_file(&path));
+        let rx = spawn(IpcServer::new(UnixListener::bind(&path)?, JsonCodec, ()));
+
+        let mut dispatcher = IpcDispatcher::new(UnixStream::connect(&path)?, JsonCodec);
+
+        for &action in &actions {
+            assert_eq!(dispatcher.dispatch(action).ok(), Some(()));
+        }
+
+        std::fs::remove_file(&path)?;
+
+        assert_eq!(rx.iter().take(actions.len()).collect::<Vec<_>>(), actions);
+    }
+
+    #[proptest]
+    #[cfg_attr(miri, ignore)] // Sockets are not supported.
+    fn invalid(action: u8) {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let rx = spawn(IpcServer::new(listener, JsonCodec, ()));
+
+        let mut stream = TcpStream::connect(addr)?;
+        stream.write_all(&frame(b"not json".to_vec())?)?;
+
+        // The connection is closed without dispatching.
+        assert_eq!(stream.read(&mut [0])?, 0);
+        assert!(rx.try_recv().is_err());
+
+        let mut dispatcher = IpcDispatcher::new(TcpStream::connect(addr)?, JsonCodec);
+        assert_eq!(dispatcher.dispatch(action).ok(), Some(()));
+        assert_eq!(rx.recv().ok(), Some(action));
+    }
+
+    #[proptest]
+    #[cfg_attr(miri, ignore)] // Sockets are not supported.
+    fn binary(actions: Vec<u8>) {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let rx = spawn(IpcServer::new(listener, Byte, ()).with_max_frame(1));
+
+        let mut stream = TcpStream::connect(addr)?;
+        let mut dispatcher = IpcDispatcher::new(stream.try_clone()?, Byte);
+
+        for &action in &actions {
+            assert_eq!(dispatcher.dispatch(action).ok(), Some(()));
+        }
+
+        assert_eq!(rx.iter().take(actions.len()).collect::<Vec<_>>(), actions);
+
+        // Exactly one byte is sent per action.
+        stream.write_all(&[0, 0, 0, 1, 42])?;
+        assert_eq!(rx.recv().ok(), Some(42));
+    }
+
+    #[proptest]
+    #[cfg_attr(miri, ignore)] // Sockets are not supported.
+    fn oversized(#[strategy(3usize..64)] max: usize, action: u8) {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let rx = spawn(IpcServer::new(listener, JsonCodec, ()).with_max_frame(max));
+
+        // The length is rejected before the payload is even sent.
+        let mut stream = TcpStream::connect(addr)?;
+        stream.write_all(&u32::MAX.to_be_bytes())?;
+
+        assert_eq!(stream.read(&mut [0])?, 0);
+        assert!(rx.try_recv().is_err());
+
+        // A u8 takes at most 3 bytes as JSON, so it fits in the frame.
+        let mut dispatcher = IpcDispatcher::new(TcpStream::connect(addr)?, JsonCodec);
+        assert_eq!(dispatcher.dispatch(action).ok(), Some(()));
+        assert_eq!(rx.recv().ok(), Some(action));
+    }
+}
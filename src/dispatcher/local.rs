@@ -0,0 +1,399 @@
+use crate::dispatcher::{Dispatcher, Store};
+use crate::reactor::Reactor;
+use crate::reducer::Reducer;
+use alloc::{boxed::Box, collections::VecDeque, rc::Rc};
+use core::cell::{Ref, RefCell};
+use core::fmt::{self, Debug, Formatter};
+
+type Deferred<S> = Box<dyn FnOnce(&mut S)>;
+
+/// Discards pending actions when dropped, even if the [`Reactor`] panics.
+struct Discard<'a, S>(&'a RefCell<VecDeque<Deferred<S>>>);
+
+impl<S> Drop for Discard<'_, S> {
+    fn drop(&mut self) {
+        drop(self.0.take());
+    }
+}
+
+struct Inner<S, R> {
+    state: RefCell<S>,
+    reactor: RefCell<R>,
+    deferred: RefCell<VecDeque<Deferred<S>>>,
+}
+
+/// A reactive state container that can be shared within a single thread (requires [`alloc`]).
+///
+/// [`LocalStore`] is a lightweight handle that can be cloned and handed to the various parts
+/// of a single-threaded application, e.g. widgets of a graphical user interface,
+/// all clones refer to the same state and [`Reactor`].
+/// Just like [`Store`], the only way to mutate the state is by [dispatching] actions on it,
+/// but [`LocalStore`] only requires a shared reference to do so.
+///
+/// Actions dispatched from within the [`Reactor`] are deferred until it returns,
+/// at which point they are dispatched in FIFO order.
+/// If the [`Reactor`] fails or panics, all pending actions are discarded.
+///
+/// [`alloc`]: index.html#optional-features
+/// [dispatching]: LocalStore::dispatch
+///
+/// # Example
+///
+/// ```rust
+/// use reducer::*;
+/// use std::convert::Infallible;
+///
+/// #[derive(Debug, Default)]
+/// struct Editor {
+///     text: String,
+///     saved: bool,
+/// }
+///
+/// enum Action {
+///     Type(char),
+///     Save,
+/// }
+///
+/// impl Reducer<Action> for Editor {
+///     fn reduce(&mut self, action: Action) {
+///         match action {
+///             Action::Type(c) => {
+///                 self.text.push(c);
+///                 self.saved = false;
+///             }
+///
+///             Action::Save => self.saved = true,
+///         }
+///     }
+/// }
+///
+/// struct Autosave(Option<LocalStore<Editor, Autosave>>);
+///
+/// impl Reactor<Editor> for Autosave {
+///     type Error = Infallible;
+///
+///     fn react(&mut self, state: &Editor) -> Result<(), Self::Error> {
+///         match &self.0 {
+///             // Dispatched once the current action completes.
+///             Some(store) if !state.saved => store.dispatch(Action::Save),
+///             _ => Ok(()),
+///         }
+///     }
+/// }
+///
+/// let store = LocalStore::new(Editor::default(), Autosave(None));
+/// let widget = store.clone();
+/// store.subscribe(Autosave(Some(widget.clone())));
+///
+/// widget.dispatch(Action::Type('a')).unwrap();
+/// assert!(store.read().saved);
+///
+/// // Break the reference cycle.
+/// store.subscribe(Autosave(None));
+/// ```
+pub struct LocalStore<S, R> {
+    inner: Rc<Inner<S, R>>,
+}
+
+impl<S: Debug, R: Debug> Debug for LocalStore<S, R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalStore")
+            .field("state", &self.inner.state)
+            .field("reactor", &self.inner.reactor)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S: Default, R: Default> Default for LocalStore<S, R> {
+    fn default() -> Self {
+        Self::new(S::default(), R::default())
+    }
+}
+
+impl<S, R> Clone for LocalStore<S, R> {
+    fn clone(&self) -> Self {
+        LocalStore {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<S, R> From<Store<S, R>> for LocalStore<S, R> {
+    fn from(store: Store<S, R>) -> Self {
+        let (state, reactor) = store.into_parts();
+        Self::new(state, reactor)
+    }
+}
+
+impl<S, R> LocalStore<S, R> {
+    /// Constructs the LocalStore given the initial state and a [`Reactor`].
+    pub fn new(state: S, reactor: R) -> Self {
+        LocalStore {
+            inner: Rc::new(Inner {
+                state: RefCell::new(state),
+                reactor: RefCell::new(reactor),
+                deferred: RefCell::new(VecDeque::new()),
+            }),
+        }
+    }
+
+    /// Immutably borrows the state.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called while an action is being reduced.
+    pub fn read(&self) -> Ref<'_, S> {
+        self.inner.state.borrow()
+    }
+
+    /// Replaces the [`Reactor`] and returns the previous one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from within the [`Reactor`].
+    pub fn subscribe(&self, reactor: impl Into<R>) -> R {
+        self.inner.reactor.replace(reactor.into())
+    }
+
+    /// Updates the state via [`Reducer::reduce`] and notifies the [`Reactor`],
+    /// returning the result of calling [`Reactor::react`] with a reference
+    /// to the new state.
+    ///
+    /// If called from within the [`Reactor`], the action is deferred and `Ok(())` is returned
+    /// immediately, while the outcome of dispatching it is reported by the outer call instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called while the state is borrowed through [`LocalStore::read`].
+    pub fn dispatch<A>(&self, action: A) -> Result<(), R::Error>
+    where
+        S: Reducer<A>,
+        R: Reactor<S>,
+        A: 'static,
+    {
+        let mut reactor = match self.inner.reactor.try_borrow_mut() {
+            Ok(reactor) => reactor,
+            Err(_) => {
+                let deferred = Box::new(move |state: &mut S| state.reduce(action));
+                self.inner.deferred.borrow_mut().push_back(deferred);
+                return Ok(());
+            }
+        };
+
+        let _discard = Discard(&self.inner.deferred);
+        self.inner.state.borrow_mut().reduce(action);
+
+        loop {
+            reactor.react(&self.inner.state.borrow())?;
+
+            match self.inner.deferred.borrow_mut().pop_front() {
+                Some(reduce) => reduce(&mut self.inner.state.borrow_mut()),
+                None => return Ok(()),
+            }
+        }
+    }
+}
+
+impl<A, S, R> Dispatcher<A> for LocalStore<S, R>
+where
+    S: Reducer<A>,
+    R: Reactor<S>,
+    A: 'static,
+{
+    type Output = Result<(), R::Error>;
+
+    /// See [`LocalStore::dispatch`].
+    fn dispatch(&mut self, action: A) -> Self::Output {
+        LocalStore::dispatch(self, action)
+    }
+}
+
+impl<A, S, R> Dispatcher<A> for &LocalStore<S, R>
+where
+    S: Reducer<A>,
+    R: Reactor<S>,
+    A: 'static,
+{
+    type Output = Result<(), R::Error>;
+
+    /// See [`LocalStore::dispatch`].
+    fn dispatch(&mut self, action: A) -> Self::Output {
+        LocalStore::dispatch(self, action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reactor::MockReactor;
+    use crate::reducer::MockReducer;
+    use core::cell::OnceCell;
+    use mockall::{predicate::*, Sequence};
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::vec::Vec;
+    use test_strategy::proptest;
+
+    #[proptest]
+    fn default() {
+        LocalStore::<(), ()>::default();
+    }
+
+    #[proptest]
+    fn new(state: u8, reactor: u8) {
+        let store = LocalStore::new(state, reactor);
+        assert_eq!(*store.read(), state);
+        assert_eq!(*store.inner.reactor.borrow(), reactor);
+    }
+
+    #[proptest]
+    fn from(state: u8, reactor: u8) {
+        let store = LocalStore::from(Store::new(state, reactor));
+        assert_eq!(*store.read(), state);
+        assert_eq!(*store.inner.reactor.borrow(), reactor);
+    }
+
+    #[proptest]
+    fn clone(state: u8, a: u8, b: u8) {
+        let store = LocalStore::new(state, a);
+        let other = store.clone();
+        assert_eq!(other.subscribe(b), a);
+        assert_eq!(*store.inner.reactor.borrow(), b);
+    }
+
+    #[proptest]
+    fn dispatch(action: u8, result: Result<(), u8>, id: usize) {
+        let mut reducer = MockReducer::new();
+        reducer.expect_id().return_const(id);
+        reducer
+            .expect_reduce()
+            .with(eq(action))
+            .times(2)
+            .return_const(());
+
+        let mut reactor = MockReactor::new();
+        reactor
+            .expect_react()
+            .with(function(move |x: &MockReducer<_>| x.id() == id))
+            .times(2)
+            .return_const(result);
+
+        let mut store = LocalStore::new(reducer, reactor);
+        assert_eq!(Dispatcher::dispatch(&mut store, action), result);
+        assert_eq!(Dispatcher::dispatch(&mut &store, action), result);
+    }
+
+    type Handle = Rc<OnceCell<LocalStore<MockReducer<u8>, MockReactor<MockReducer<u8>, u8>>>>;
+
+    #[proptest]
+    fn reentrant(action: u8, followups: Vec<u8>) {
+        let handle: Handle = Rc::default();
+        let mut reducer = MockReducer::new();
+        let mut reactor = MockReactor::new();
+        let mut seq = Sequence::new();
+
+        reducer
+            .expect_reduce()
+            .with(eq(action))
+            .once()
+            .in_sequence(&mut seq)
+            .return_const(());
+
+        let (store, actions) = (handle.clone(), followups.clone());
+        reactor
+            .expect_react()
+            .once()
+            .in_sequence(&mut seq)
+            .returning_st(move |_| {
+                for &a in &actions {
+                    assert_eq!(store.get().unwrap().dispatch(a), Ok(()));
+                }
+
+                Ok(())
+            });
+
+        for &a in &followups {
+            reducer
+                .expect_reduce()
+                .with(eq(a))
+                .once()
+                .in_sequence(&mut seq)
+                .return_const(());
+
+            reactor
+                .expect_react()
+                .once()
+                .in_sequence(&mut seq)
+                .return_const(Ok(()));
+        }
+
+        let store = LocalStore::new(reducer, reactor);
+        assert!(handle.set(store.clone()).is_ok());
+        assert_eq!(store.dispatch(action), Ok(()));
+
+        // Break the reference cycle.
+        store.subscribe(MockReactor::new());
+    }
+
+    #[proptest]
+    fn error(action: u8, followups: Vec<u8>, error: u8) {
+        let handle: Handle = Rc::default();
+        let mut reducer = MockReducer::new();
+        let mut reactor = MockReactor::new();
+
+        reducer
+            .expect_reduce()
+            .with(eq(action))
+            .once()
+            .return_const(());
+
+        let store = handle.clone();
+        reactor.expect_react().once().returning_st(move |_| {
+            for &a in &followups {
+                assert_eq!(store.get().unwrap().dispatch(a), Ok(()));
+            }
+
+            Err(error)
+        });
+
+        let store = LocalStore::new(reducer, reactor);
+        assert!(handle.set(store.clone()).is_ok());
+        assert_eq!(store.dispatch(action), Err(error));
+        assert!(store.inner.deferred.borrow().is_empty());
+
+        store.subscribe(MockReactor::new());
+    }
+
+    #[proptest]
+    fn panic(action: u8, followups: Vec<u8>, result: Result<(), u8>) {
+        let handle: Handle = Rc::default();
+        let mut reducer = MockReducer::new();
+        let mut reactor = MockReactor::new();
+
+        reducer
+            .expect_reduce()
+            .with(eq(action))
+            .times(2)
+            .return_const(());
+
+        let store = handle.clone();
+        reactor.expect_react().once().returning_st(move |_| {
+            for &a in &followups {
+                assert_eq!(store.get().unwrap().dispatch(a), Ok(()));
+            }
+
+            panic!("reactor panicked");
+        });
+
+        reactor.expect_react().once().return_const(result);
+
+        let store = LocalStore::new(reducer, reactor);
+        assert!(handle.set(store.clone()).is_ok());
+        assert!(catch_unwind(AssertUnwindSafe(|| store.dispatch(action))).is_err());
+        assert!(store.inner.deferred.borrow().is_empty());
+
+        // Pending actions are not applied by the next dispatch.
+        assert_eq!(store.dispatch(action), result);
+
+        store.subscribe(MockReactor::new());
+    }
+}
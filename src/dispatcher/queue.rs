@@ -0,0 +1,361 @@
+use crate::dispatcher::Dispatcher;
+use alloc::collections::VecDeque;
+use alloc::rc::Rc;
+use core::cell::RefCell;
+use derive_more::{Deref, Display};
+
+#[cfg(feature = "std")]
+use derive_more::Error;
+
+/// A handle through which actions can be enqueued while another action is being dispatched
+/// (requires [`alloc`]).
+///
+/// [`DispatchQueue`] is typically handed to [`Reactor`](crate::Reactor)s, which may then
+/// dispatch follow-up actions on the [`Queued`] dispatcher that drains it.
+/// Clones refer to the same queue.
+///
+/// [`alloc`]: index.html#optional-features
+#[derive(Debug)]
+pub struct DispatchQueue<A>(Rc<RefCell<VecDeque<A>>>);
+
+impl<A> Default for DispatchQueue<A> {
+    fn default() -> Self {
+        DispatchQueue(Default::default())
+    }
+}
+
+impl<A> Clone for DispatchQueue<A> {
+    fn clone(&self) -> Self {
+        DispatchQueue(self.0.clone())
+    }
+}
+
+impl<A> DispatchQueue<A> {
+    /// Constructs an empty [`DispatchQueue`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enqueues an action to be dispatched once the current dispatch completes.
+    pub fn enqueue(&self, action: A) {
+        self.0.borrow_mut().push_back(action);
+    }
+
+    /// The number of pending actions.
+    pub fn len(&self) -> usize {
+        self.0.borrow().len()
+    }
+
+    /// Whether there are no pending actions.
+    pub fn is_empty(&self) -> bool {
+        self.0.borrow().is_empty()
+    }
+
+    fn dequeue(&self) -> Option<A> {
+        self.0.borrow_mut().pop_front()
+    }
+
+    fn clear(&self) {
+        self.0.borrow_mut().clear();
+    }
+}
+
+impl<A> Dispatcher<A> for DispatchQueue<A> {
+    type Output = ();
+
+    /// Enqueues the action, see [`DispatchQueue::enqueue`].
+    fn dispatch(&mut self, action: A) {
+        self.enqueue(action);
+    }
+}
+
+/// The error returned when dispatching an action through [`Queued`] fails (requires [`alloc`]).
+///
+/// [`alloc`]: index.html#optional-features
+#[derive(Debug, Display, Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "std", derive(Error))]
+pub enum QueueError<E> {
+    /// The underlying [`Dispatcher`] failed.
+    #[display(fmt = "{}", _0)]
+    Dispatch(#[cfg_attr(feature = "std", error(not(source)))] E),
+
+    /// Actions kept being enqueued past the maximum depth, which likely indicates an infinite
+    /// loop of follow-up actions.
+    #[display(fmt = "Exceeded the maximum depth of {} follow-up actions", _0)]
+    DepthExceeded(#[cfg_attr(feature = "std", error(not(source)))] usize),
+}
+
+/// A [`Dispatcher`] that drains a [`DispatchQueue`] after every dispatch (requires [`alloc`]).
+///
+/// Actions enqueued while an action is being dispatched are dispatched in FIFO order
+/// once it completes.
+/// Follow-up actions may enqueue further actions, up to the maximum _depth_ of the chain.
+///
+/// If the underlying [`Dispatcher`] fails or the maximum depth is exceeded,
+/// all pending actions are discarded.
+///
+/// [`alloc`]: index.html#optional-features
+///
+/// # Example
+///
+/// ```rust
+/// use reducer::*;
+/// use std::convert::Infallible;
+///
+/// #[derive(Default)]
+/// struct Session {
+///     user: Option<String>,
+///     profile: Option<String>,
+/// }
+///
+/// enum Action {
+///     LoginSucceeded(String),
+///     LoadProfile(String),
+/// }
+///
+/// impl Reducer<Action> for Session {
+///     fn reduce(&mut self, action: Action) {
+///         match action {
+///             Action::LoginSucceeded(user) => self.user = Some(user),
+///             Action::LoadProfile(user) => self.profile = Some(format!("{}'s profile", user)),
+///         }
+///     }
+/// }
+///
+/// struct Loader {
+///     queue: DispatchQueue<Action>,
+/// }
+///
+/// impl Reactor<Session> for Loader {
+///     type Error = Infallible;
+///
+///     fn react(&mut self, state: &Session) -> Result<(), Self::Error> {
+///         if let (Some(user), None) = (&state.user, &state.profile) {
+///             // On login success, load the profile.
+///             self.queue.enqueue(Action::LoadProfile(user.clone()));
+///         }
+///
+///         Ok(())
+///     }
+/// }
+///
+/// let queue = DispatchQueue::new();
+/// let store = Store::new(Session::default(), Loader { queue: queue.clone() });
+/// let mut store = Queued::new(store, queue, 8);
+///
+/// store.dispatch(Action::LoginSucceeded("Alice".into())).unwrap();
+/// assert_eq!(store.profile.as_deref(), Some("Alice's profile"));
+/// ```
+#[derive(Debug, Clone, Deref)]
+pub struct Queued<D, A> {
+    #[deref]
+    dispatcher: D,
+    queue: DispatchQueue<A>,
+    max_depth: usize,
+}
+
+impl<D, A> Queued<D, A> {
+    /// Constructs a [`Queued`] dispatcher given the underlying [`Dispatcher`], the
+    /// [`DispatchQueue`] to be drained and the maximum depth of follow-up actions.
+    pub fn new(dispatcher: D, queue: DispatchQueue<A>, max_depth: usize) -> Self {
+        Queued {
+            dispatcher,
+            queue,
+            max_depth,
+        }
+    }
+
+    /// Returns the underlying [`Dispatcher`].
+    pub fn into_inner(self) -> D {
+        self.dispatcher
+    }
+}
+
+impl<D, A, E> Dispatcher<A> for Queued<D, A>
+where
+    D: Dispatcher<A, Output = Result<(), E>>,
+{
+    type Output = Result<(), QueueError<E>>;
+
+    /// Enqueues the action and then dispatches all pending actions, in FIFO order.
+    ///
+    /// Actions enqueued before this call are dispatched ahead of the action.
+    fn dispatch(&mut self, action: A) -> Self::Output {
+        self.queue.enqueue(action);
+
+        let mut pending = self.queue.len();
+        let mut depth = 0;
+
+        while let Some(action) = self.queue.dequeue() {
+            if let Err(e) = self.dispatcher.dispatch(action) {
+                self.queue.clear();
+                return Err(QueueError::Dispatch(e));
+            }
+
+            pending -= 1;
+
+            if pending == 0 {
+                pending = self.queue.len();
+
+                if pending > 0 {
+                    depth += 1;
+                }
+
+                if depth > self.max_depth {
+                    self.queue.clear();
+                    return Err(QueueError::DepthExceeded(self.max_depth));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dispatcher::MockDispatcher;
+    use alloc::vec::Vec;
+    use mockall::{predicate::*, Sequence};
+    use test_strategy::proptest;
+
+    #[proptest]
+    fn queue(actions: Vec<u8>) {
+        let queue = DispatchQueue::new();
+        let mut other = queue.clone();
+
+        assert!(queue.is_empty());
+
+        for &action in &actions {
+            other.dispatch(action);
+        }
+
+        assert_eq!(queue.len(), actions.len());
+
+        for &action in &actions {
+            assert_eq!(queue.dequeue(), Some(action));
+        }
+
+        assert!(queue.is_empty());
+    }
+
+    #[proptest]
+    fn dispatch(action: u8, result: Result<(), u8>) {
+        let mut mock = MockDispatcher::new();
+
+        mock.expect_dispatch()
+            .with(eq(action))
+            .once()
+            .return_const(result);
+
+        let mut dispatcher = Queued::new(mock, DispatchQueue::new(), 0);
+        assert_eq!(
+            Dispatcher::dispatch(&mut dispatcher, action),
+            result.map_err(QueueError::Dispatch)
+        );
+    }
+
+    #[proptest]
+    fn chain(#[strategy(0u8..32)] length: u8, #[strategy(0usize..32)] max_depth: usize) {
+        let queue = DispatchQueue::new();
+        let mut mock = MockDispatcher::<u8, Result<(), ()>>::new();
+        let mut seq = Sequence::new();
+
+        for action in (0..=length).rev().take(max_depth + 1) {
+            let queue = queue.clone();
+            mock.expect_dispatch()
+                .with(eq(action))
+                .once()
+                .in_sequence(&mut seq)
+                .returning_st(move |a| {
+                    if a > 0 {
+                        queue.enqueue(a - 1);
+                    }
+
+                    Ok(())
+                });
+        }
+
+        let mut dispatcher = Queued::new(mock, queue.clone(), max_depth);
+
+        let expected = if usize::from(length) > max_depth {
+            Err(QueueError::DepthExceeded(max_depth))
+        } else {
+            Ok(())
+        };
+
+        assert_eq!(Dispatcher::dispatch(&mut dispatcher, length), expected);
+        assert!(queue.is_empty());
+    }
+
+    #[proptest]
+    fn fifo(#[strategy(1u8..16)] fanout: u8) {
+        let queue = DispatchQueue::new();
+        let mut mock = MockDispatcher::<u8, Result<(), ()>>::new();
+        let mut seq = Sequence::new();
+
+        let q = queue.clone();
+        mock.expect_dispatch()
+            .with(eq(0))
+            .once()
+            .in_sequence(&mut seq)
+            .returning_st(move |_| {
+                (1..=fanout).for_each(|a| q.enqueue(a));
+                Ok(())
+            });
+
+        for action in 1..=fanout {
+            mock.expect_dispatch()
+                .with(eq(action))
+                .once()
+                .in_sequence(&mut seq)
+                .return_const(Ok(()));
+        }
+
+        // Fan-out doesn't count towards the depth.
+        let mut dispatcher = Queued::new(mock, queue, 1);
+        assert_eq!(Dispatcher::dispatch(&mut dispatcher, 0), Ok(()));
+    }
+
+    #[proptest]
+    fn backlog(backlog: Vec<u8>, action: u8) {
+        let queue = DispatchQueue::new();
+        let mut mock = MockDispatcher::<u8, Result<(), ()>>::new();
+        let mut seq = Sequence::new();
+
+        backlog.iter().for_each(|&a| queue.enqueue(a));
+
+        for &a in backlog.iter().chain([&action]) {
+            mock.expect_dispatch()
+                .with(eq(a))
+                .once()
+                .in_sequence(&mut seq)
+                .return_const(Ok(()));
+        }
+
+        let mut dispatcher = Queued::new(mock, queue.clone(), 0);
+        assert_eq!(Dispatcher::dispatch(&mut dispatcher, action), Ok(()));
+        assert!(queue.is_empty());
+    }
+
+    #[proptest]
+    fn error(actions: Vec<u8>, error: u8) {
+        let queue = DispatchQueue::new();
+        let mut mock = MockDispatcher::new();
+
+        let q = queue.clone();
+        mock.expect_dispatch().once().returning_st(move |_| {
+            actions.iter().for_each(|&a| q.enqueue(a));
+            Err(error)
+        });
+
+        let mut dispatcher = Queued::new(mock, queue.clone(), usize::MAX);
+        assert_eq!(
+            Dispatcher::dispatch(&mut dispatcher, 0),
+            Err(QueueError::Dispatch(error))
+        );
+
+        assert!(queue.is_empty());
+    }
+}
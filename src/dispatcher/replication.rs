@@ -0,0 +1,526 @@
+use crate::dispatcher::{Dispatcher, Store};
+use crate::reactor::Reactor;
+use crate::reducer::Reducer;
+use core::ops::Deref;
+use derive_more::{Display, Error};
+use futures::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+use std::io::{self, ErrorKind};
+use std::string::String;
+use std::vec::Vec;
+
+/// The error returned when replicating a [`Store`] fails (requires [`replication`]).
+///
+/// [`replication`]: index.html#optional-features
+#[derive(Debug, Display, Error)]
+pub enum ReplicationError<E> {
+    /// Reading, writing or (de)serializing a frame failed.
+    #[display(fmt = "{}", _0)]
+    Io(io::Error),
+
+    /// The [`Follower`] missed some actions, which likely indicates it lagged behind.
+    #[display(fmt = "Expected action #{} but received action #{}", expected, found)]
+    OutOfOrder {
+        /// The sequence number the [`Follower`] expected.
+        expected: u64,
+        /// The sequence number the [`Follower`] received.
+        found: u64,
+    },
+
+    /// The [`Reactor`] failed.
+    #[display(fmt = "{}", _0)]
+    Reactor(#[error(not(source))] E),
+}
+
+impl<E> From<io::Error> for ReplicationError<E> {
+    fn from(error: io::Error) -> Self {
+        ReplicationError::Io(error)
+    }
+}
+
+/// The default maximum length of the frames a [`Follower`] accepts.
+const MAX_FRAME: usize = 16 << 20;
+
+fn encode(sequence: u64, key: &str, value: &impl Serialize) -> io::Result<Vec<u8>> {
+    let mut frame = serde_json::to_vec(&json!({ "sequence": sequence, key: value }))?;
+    frame.push(b'\n');
+    Ok(frame)
+}
+
+/// A [`Store`] whose actions are replicated to [`Follower`]s (requires [`replication`]).
+///
+/// Every action dispatched on the [`Leader`] is assigned a sequence number and sent to all
+/// attached [`Follower`]s, which apply it to their own copy of the state.
+/// Since [`Reducer`]s are deterministic, the copies stay identical to the state of the leader.
+/// [`Follower`]s may join late, in which case they catch up from a snapshot of the state.
+///
+/// Frames are newline-delimited JSON objects written to any [`AsyncWrite`],
+/// such as a socket or a pipe.
+/// Actions of a single type must be dispatched, since [`Follower`]s can only deserialize
+/// actions of the type they expect, which is typically an enum.
+///
+/// [`Follower`]s that can't be written to are detached, while slow ones slow down the leader.
+///
+/// [`replication`]: index.html#optional-features
+///
+/// # Example
+///
+/// ```rust
+/// use reducer::*;
+/// use serde::{Deserialize, Serialize};
+/// use std::convert::Infallible;
+///
+/// #[derive(Default, Serialize, Deserialize)]
+/// struct Counter(i32);
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Add(i32);
+///
+/// impl Reducer<Add> for Counter {
+///     fn reduce(&mut self, Add(x): Add) {
+///         self.0 += x;
+///     }
+/// }
+///
+/// struct Nop;
+///
+/// impl<S> Reactor<S> for Nop {
+///     type Error = Infallible;
+///     fn react(&mut self, _: &S) -> Result<(), Self::Error> {
+///         Ok(())
+///     }
+/// }
+///
+/// futures::executor::block_on(async {
+///     let mut leader = Leader::new(Store::new(Counter::default(), Nop));
+///     leader.dispatch(Add(1)).await.unwrap();
+///
+///     // Any AsyncWrite will do, e.g. a socket to another process.
+///     let mut pipe = Vec::new();
+///     leader.attach(&mut pipe).await.unwrap();
+///     leader.dispatch(Add(2)).await.unwrap();
+///     drop(leader);
+///
+///     // The follower catches up from a snapshot.
+///     let follower = Follower::<Counter, _, _>::join(&pipe[..], Nop).await.unwrap();
+///     assert_eq!(follower.0, 1);
+///
+///     let store = follower.run::<Add>().await.unwrap();
+///     assert_eq!(store.0, 3);
+/// });
+/// ```
+#[derive(Debug)]
+pub struct Leader<S, R, W> {
+    store: Store<S, R>,
+    followers: Vec<W>,
+    sequence: u64,
+}
+
+impl<S, R, W> Deref for Leader<S, R, W> {
+    type Target = S;
+
+    fn deref(&self) -> &S {
+        &self.store
+    }
+}
+
+impl<S, R, W> Leader<S, R, W> {
+    /// Starts replicating the [`Store`].
+    pub fn new(store: Store<S, R>) -> Self {
+        Leader {
+            store,
+            followers: Vec::new(),
+            sequence: 0,
+        }
+    }
+
+    /// The number of actions dispatched so far.
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// The number of attached [`Follower`]s.
+    pub fn followers(&self) -> usize {
+        self.followers.len()
+    }
+
+    /// Replaces the [`Reactor`] and returns the previous one.
+    pub fn subscribe(&mut self, reactor: impl Into<R>) -> R {
+        self.store.subscribe(reactor)
+    }
+
+    /// Stops replicating and returns the [`Store`].
+    pub fn into_store(self) -> Store<S, R> {
+        self.store
+    }
+
+    /// Sends a snapshot of the state to a [`Follower`],
+    /// which then receives every action dispatched from now on.
+    pub async fn attach(&mut self, mut writer: W) -> io::Result<()>
+    where
+        S: Serialize,
+        W: AsyncWrite + Unpin,
+    {
+        let frame = encode(self.sequence, "snapshot", &*self.store)?;
+        writer.write_all(&frame).await?;
+        writer.flush().await?;
+        self.followers.push(writer);
+        Ok(())
+    }
+
+    /// Dispatches the action on the [`Store`] and sends it to all attached [`Follower`]s.
+    ///
+    /// The action is only dispatched if it can be serialized.
+    pub async fn dispatch<A>(&mut self, action: A) -> Result<(), ReplicationError<R::Error>>
+    where
+        A: Serialize,
+        S: Reducer<A>,
+        R: Reactor<S>,
+        W: AsyncWrite + Unpin,
+    {
+        let frame = encode(self.sequence + 1, "action", &action)?;
+        self.sequence += 1;
+
+        let result = self.store.dispatch(action);
+
+        let mut attached = Vec::with_capacity(self.followers.len());
+
+        for mut follower in self.followers.drain(..) {
+            if follower.write_all(&frame).await.is_ok() && follower.flush().await.is_ok() {
+                attached.push(follower);
+            }
+        }
+
+        self.followers = attached;
+        result.map_err(ReplicationError::Reactor)
+    }
+}
+
+/// A [`Store`] that mirrors the state of a [`Leader`] (requires [`replication`]).
+///
+/// See [`Leader`] for an example.
+///
+/// [`replication`]: index.html#optional-features
+#[derive(Debug)]
+pub struct Follower<S, R, Rd> {
+    store: Store<S, R>,
+    reader: BufReader<Rd>,
+    sequence: u64,
+    line: String,
+    max_frame: usize,
+}
+
+impl<S, R, Rd> Deref for Follower<S, R, Rd> {
+    type Target = S;
+
+    fn deref(&self) -> &S {
+        &self.store
+    }
+}
+
+impl<S, R, Rd> Follower<S, R, Rd>
+where
+    S: DeserializeOwned,
+    R: Reactor<S>,
+    Rd: AsyncRead + Unpin,
+{
+    /// Waits for the snapshot sent by the [`Leader`] upon [attaching] and constructs the
+    /// [`Store`] from it, notifying the [`Reactor`] of the initial state.
+    ///
+    /// Frames longer than 16 MiB, excluding the trailing newline, are rejected with
+    /// [`ErrorKind::InvalidData`], see [`Follower::join_with_max_frame`] to change the limit.
+    ///
+    /// [attaching]: Leader::attach
+    pub async fn join(reader: Rd, reactor: R) -> Result<Self, ReplicationError<R::Error>> {
+        Self::join_with_max_frame(reader, reactor, MAX_FRAME).await
+    }
+
+    /// Like [`Follower::join`], but rejects frames longer than `max_frame` bytes,
+    /// excluding the trailing newline, which keeps the [`Leader`] from making the
+    /// [`Follower`] allocate arbitrary amounts of memory.
+    pub async fn join_with_max_frame(
+        reader: Rd,
+        mut reactor: R,
+        max_frame: usize,
+    ) -> Result<Self, ReplicationError<R::Error>> {
+        let mut reader = BufReader::new(reader);
+        let mut line = String::new();
+
+        let (sequence, frame) = match read(&mut reader, &mut line, max_frame).await? {
+            Some((sequence, mut frame)) if frame.get("snapshot").is_some() => {
+                (sequence, frame["snapshot"].take())
+            }
+
+            _ => return Err(invalid("expected a snapshot").into()),
+        };
+
+        let state: S = serde_json::from_value(frame).map_err(io::Error::from)?;
+        reactor.react(&state).map_err(ReplicationError::Reactor)?;
+
+        Ok(Follower {
+            store: Store::new(state, reactor),
+            reader,
+            sequence,
+            line,
+            max_frame,
+        })
+    }
+
+    /// The number of actions the state accounts for.
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Waits for the next action sent by the [`Leader`] and dispatches it on the [`Store`].
+    ///
+    /// Returns `None` once the [`Leader`] detaches.
+    pub async fn next<A>(&mut self) -> Option<Result<(), ReplicationError<R::Error>>>
+    where
+        A: DeserializeOwned,
+        S: Reducer<A>,
+    {
+        let (sequence, mut frame) =
+            match read(&mut self.reader, &mut self.line, self.max_frame).await {
+                Ok(Some(frame)) => frame,
+                Ok(None) => return None,
+                Err(e) => return Some(Err(e.into())),
+            };
+
+        if sequence != self.sequence + 1 {
+            return Some(Err(ReplicationError::OutOfOrder {
+                expected: self.sequence + 1,
+                found: sequence,
+            }));
+        }
+
+        let action: A = match frame.get_mut("action").map(Value::take) {
+            Some(action) => match serde_json::from_value(action) {
+                Ok(action) => action,
+                Err(e) => return Some(Err(io::Error::from(e).into())),
+            },
+
+            None => return Some(Err(invalid("expected an action").into())),
+        };
+
+        self.sequence = sequence;
+        Some(
+            self.store
+                .dispatch(action)
+                .map_err(ReplicationError::Reactor),
+        )
+    }
+
+    /// Mirrors the state of the [`Leader`] until it detaches and returns the [`Store`].
+    pub async fn run<A>(mut self) -> Result<Store<S, R>, ReplicationError<R::Error>>
+    where
+        A: DeserializeOwned,
+        S: Reducer<A>,
+    {
+        while let Some(result) = self.next::<A>().await {
+            result?;
+        }
+
+        Ok(self.store)
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, message)
+}
+
+/// Reads the next frame, returning its sequence number and contents.
+///
+/// Frames longer than `max` are rejected before they are read in full.
+async fn read<Rd>(
+    reader: &mut BufReader<Rd>,
+    line: &mut String,
+    max: usize,
+) -> io::Result<Option<(u64, Value)>>
+where
+    Rd: AsyncRead + Unpin,
+{
+    line.clear();
+
+    // Reading one more byte than allowed leaves room for the trailing newline.
+    let limit = u64::try_from(max).map_or(u64::MAX, |max| max.saturating_add(1));
+
+    match reader.take(limit).read_line(line).await? {
+        0 => return Ok(None),
+        len if len > max && !line.ends_with('\n') => {
+            return Err(invalid("frame too large"));
+        }
+        _ => {}
+    }
+
+    let frame: Value = serde_json::from_str(line)?;
+
+    match frame["sequence"].as_u64() {
+        Some(sequence) => Ok(Some((sequence, frame))),
+        None => Err(invalid("expected a sequence number")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reactor::MockReactor;
+    use futures::executor::block_on;
+    use serde::Deserialize;
+    use test_strategy::proptest;
+
+    #[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+    struct History(Vec<u8>);
+
+    impl Reducer<u8> for History {
+        fn reduce(&mut self, action: u8) {
+            self.0.push(action);
+        }
+    }
+
+    #[proptest]
+    fn replicate(state: Vec<u8>, before: Vec<u8>, after: Vec<u8>) {
+        let mut reactor = MockReactor::<History, ()>::new();
+        reactor.expect_react().return_const(Ok(()));
+
+        let mut leader = Leader::new(Store::new(History(state), reactor));
+
+        for &action in &before {
+            assert_eq!(block_on(leader.dispatch(action)).ok(), Some(()));
+        }
+
+        let mut pipe = Vec::new();
+        assert_eq!(block_on(leader.attach(&mut pipe)).ok(), Some(()));
+        assert_eq!(leader.followers(), 1);
+
+        for &action in &after {
+            assert_eq!(block_on(leader.dispatch(action)).ok(), Some(()));
+        }
+
+        assert_eq!(leader.sequence(), (before.len() + after.len()) as u64);
+        let leader = leader.into_store();
+
+        let mut reactor = MockReactor::<History, ()>::new();
+        reactor
+            .expect_react()
+            .times(after.len() + 1)
+            .return_const(Ok(()));
+
+        let follower = block_on(Follower::join(&pipe[..], reactor)).ok().unwrap();
+        assert_eq!(follower.sequence(), before.len() as u64);
+
+        let follower = block_on(follower.run::<u8>()).ok().unwrap();
+        assert_eq!(*follower, *leader);
+    }
+
+    #[proptest]
+    fn detach(action: u8) {
+        let mut leader = Leader::new(Store::new(History::default(), MockReactor::new()));
+        leader.subscribe({
+            let mut reactor = MockReactor::new();
+            reactor.expect_react().return_const(Ok::<_, ()>(()));
+            reactor
+        });
+
+        let mut buffer = [0u8; 0];
+        let writer = futures::io::Cursor::new(&mut buffer[..]);
+        assert!(block_on(leader.attach(writer)).is_err());
+        assert_eq!(leader.followers(), 0);
+
+        assert_eq!(block_on(leader.dispatch(action)).ok(), Some(()));
+        assert_eq!(*leader, History(vec![action]));
+    }
+
+    #[proptest]
+    fn unwritable(state: Vec<u8>, action: u8) {
+        let mut leader = Leader::new(Store::new(History(state.clone()), MockReactor::new()));
+        leader.subscribe({
+            let mut reactor = MockReactor::new();
+            reactor.expect_react().return_const(Ok::<_, ()>(()));
+            reactor
+        });
+
+        // Only leaves room for the snapshot.
+        let mut buffer = encode(0, "snapshot", &History(state.clone()))?;
+        let writer = futures::io::Cursor::new(&mut buffer[..]);
+        assert_eq!(block_on(leader.attach(writer)).ok(), Some(()));
+        assert_eq!(leader.followers(), 1);
+
+        assert_eq!(block_on(leader.dispatch(action)).ok(), Some(()));
+        assert_eq!(leader.followers(), 0);
+        assert_eq!(leader.sequence(), 1);
+
+        let mut expected = state;
+        expected.push(action);
+        assert_eq!(*leader, History(expected));
+    }
+
+    #[proptest]
+    fn error(state: Vec<u8>, action: u8, error: u8) {
+        let mut reactor = MockReactor::new();
+        reactor.expect_react().once().return_const(Err(error));
+
+        let mut leader = Leader::<_, _, Vec<u8>>::new(Store::new(History(state), reactor));
+
+        assert!(matches!(
+            block_on(leader.dispatch(action)),
+            Err(ReplicationError::Reactor(e)) if e == error
+        ));
+    }
+
+    #[proptest]
+    fn out_of_order(state: Vec<u8>, #[strategy(2u64..)] sequence: u64, action: u8) {
+        let mut pipe = Vec::new();
+        pipe.extend(encode(0, "snapshot", &History(state))?);
+        pipe.extend(encode(sequence, "action", &action)?);
+
+        let mut reactor = MockReactor::<History, ()>::new();
+        reactor.expect_react().once().return_const(Ok(()));
+
+        let mut follower = block_on(Follower::join(&pipe[..], reactor)).ok().unwrap();
+
+        assert!(matches!(
+            block_on(follower.next::<u8>()),
+            Some(Err(ReplicationError::OutOfOrder { expected: 1, found })) if found == sequence
+        ));
+    }
+
+    #[proptest]
+    fn invalid(action: u8) {
+        let pipe = encode(1, "action", &action)?;
+        let reactor = MockReactor::<History, ()>::new();
+
+        assert!(matches!(
+            block_on(Follower::join(&pipe[..], reactor)),
+            Err(ReplicationError::Io(e)) if e.kind() == ErrorKind::InvalidData
+        ));
+    }
+
+    #[proptest]
+    fn oversized(state: Vec<u8>, action: u8) {
+        let snapshot = encode(0, "snapshot", &History(state.clone()))?;
+        let max = snapshot.len() - 1;
+
+        let reactor = MockReactor::<History, ()>::new();
+        assert!(matches!(
+            block_on(Follower::join_with_max_frame(&snapshot[..], reactor, max - 1)),
+            Err(ReplicationError::Io(e)) if e.kind() == ErrorKind::InvalidData
+        ));
+
+        let mut pipe = snapshot;
+        pipe.extend(encode(1, "action", &vec![action; max])?);
+
+        let mut reactor = MockReactor::<History, ()>::new();
+        reactor.expect_react().once().return_const(Ok(()));
+
+        let mut follower = block_on(Follower::join_with_max_frame(&pipe[..], reactor, max))
+            .ok()
+            .unwrap();
+
+        assert_eq!(*follower, History(state));
+        assert!(matches!(
+            block_on(follower.next::<u8>()),
+            Some(Err(ReplicationError::Io(e))) if e.kind() == ErrorKind::InvalidData
+        ));
+    }
+}
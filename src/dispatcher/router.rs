@@ -0,0 +1,339 @@
+use crate::dispatcher::{Dispatcher, Store};
+use crate::reactor::Reactor;
+use crate::reducer::Reducer;
+use alloc::{boxed::Box, collections::BTreeMap, vec::Vec};
+use core::any::{Any, TypeId};
+use core::fmt::{self, Debug, Formatter};
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+type Route<E> = Box<dyn FnMut(&mut dyn Any, &dyn Any) -> Result<(), E>>;
+
+/// Assigns every [`Router`] a unique id, which brands the [`StoreId`]s it hands out.
+static ROUTERS: AtomicUsize = AtomicUsize::new(0);
+
+/// Identifies a [`Store`] mounted on a [`Router`] (requires [`alloc`]).
+///
+/// A [`StoreId`] only ever identifies a [`Store`] on the [`Router`] that handed it out.
+///
+/// [`alloc`]: index.html#optional-features
+pub struct StoreId<S, R> {
+    router: usize,
+    index: usize,
+    marker: PhantomData<fn() -> Store<S, R>>,
+}
+
+impl<S, R> Debug for StoreId<S, R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StoreId")
+            .field("router", &self.router)
+            .field("index", &self.index)
+            .finish()
+    }
+}
+
+impl<S, R> Copy for StoreId<S, R> {}
+
+impl<S, R> Clone for StoreId<S, R> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S, R> PartialEq for StoreId<S, R> {
+    fn eq(&self, other: &Self) -> bool {
+        self.router == other.router && self.index == other.index
+    }
+}
+
+impl<S, R> Eq for StoreId<S, R> {}
+
+/// A [`Dispatcher`] that routes actions to independent [`Store`]s by the type of the action
+/// (requires [`alloc`]).
+///
+/// [`Store`]s are [mounted] on the [`Router`] along with the types of actions they handle,
+/// which must be types their state implements [`Reducer`] for.
+/// Every action is dispatched to all [`Store`]s that handle its type in the order they were
+/// mounted, until one of them fails, while actions no [`Store`] handles are discarded.
+///
+/// The [`Router`] fails with a common error type `E`,
+/// which errors of all [`Reactor`]s must be convertible into.
+///
+/// [`alloc`]: index.html#optional-features
+/// [mounted]: Router::mount
+///
+/// # Example
+///
+/// ```rust
+/// use reducer::*;
+/// use std::convert::Infallible;
+///
+/// #[derive(Clone)]
+/// struct Edit(String);
+///
+/// #[derive(Clone)]
+/// struct Close;
+///
+/// #[derive(Default)]
+/// struct Document {
+///     text: String,
+///     open: bool,
+/// }
+///
+/// impl Reducer<Edit> for Document {
+///     fn reduce(&mut self, Edit(text): Edit) {
+///         self.text = text;
+///         self.open = true;
+///     }
+/// }
+///
+/// impl Reducer<Close> for Document {
+///     fn reduce(&mut self, _: Close) {
+///         self.open = false;
+///     }
+/// }
+///
+/// #[derive(Default)]
+/// struct Analytics {
+///     edits: usize,
+/// }
+///
+/// impl Reducer<Edit> for Analytics {
+///     fn reduce(&mut self, _: Edit) {
+///         self.edits += 1;
+///     }
+/// }
+///
+/// struct Nop;
+///
+/// impl<S> Reactor<S> for Nop {
+///     type Error = Infallible;
+///     fn react(&mut self, _: &S) -> Result<(), Self::Error> {
+///         Ok(())
+///     }
+/// }
+///
+/// let mut router = Router::<Infallible>::new();
+///
+/// let document = router
+///     .mount(Store::new(Document::default(), Nop))
+///     .route::<Edit>()
+///     .route::<Close>()
+///     .id();
+///
+/// let analytics = router
+///     .mount(Store::new(Analytics::default(), Nop))
+///     .route::<Edit>()
+///     .id();
+///
+/// router.dispatch(Edit("Hello".into())).unwrap(); // routed to both stores.
+/// router.dispatch(Close).unwrap(); // only routed to `document`.
+///
+/// assert_eq!(router.get(document).unwrap().text, "Hello");
+/// assert!(!router.get(document).unwrap().open);
+/// assert_eq!(router.get(analytics).unwrap().edits, 1);
+/// ```
+pub struct Router<E> {
+    id: usize,
+    stores: Vec<Box<dyn Any>>,
+    routes: BTreeMap<TypeId, Vec<(usize, Route<E>)>>,
+}
+
+impl<E> Debug for Router<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Router")
+            .field("stores", &self.stores.len())
+            .field("routes", &self.routes.len())
+            .finish()
+    }
+}
+
+impl<E> Default for Router<E> {
+    fn default() -> Self {
+        Router {
+            id: ROUTERS.fetch_add(1, Ordering::Relaxed),
+            stores: Vec::new(),
+            routes: BTreeMap::new(),
+        }
+    }
+}
+
+impl<E> Router<E> {
+    /// Constructs a [`Router`] with no [`Store`]s mounted.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mounts a [`Store`] on the [`Router`].
+    ///
+    /// The types of actions routed to the [`Store`] are registered through the returned [`Mount`].
+    pub fn mount<S, R>(&mut self, store: Store<S, R>) -> Mount<'_, S, R, E>
+    where
+        S: 'static,
+        R: 'static,
+    {
+        let index = self.stores.len();
+        self.stores.push(Box::new(store));
+
+        let id = StoreId {
+            router: self.id,
+            index,
+            marker: PhantomData,
+        };
+
+        Mount { router: self, id }
+    }
+
+    /// Returns a reference to a mounted [`Store`],
+    /// or `None` if `id` was handed out by another [`Router`].
+    pub fn get<S, R>(&self, id: StoreId<S, R>) -> Option<&Store<S, R>>
+    where
+        S: 'static,
+        R: 'static,
+    {
+        if id.router == self.id {
+            self.stores.get(id.index)?.downcast_ref()
+        } else {
+            None
+        }
+    }
+
+    /// Whether actions of type `A` are routed to any [`Store`].
+    pub fn is_routed<A: 'static>(&self) -> bool {
+        self.routes.contains_key(&TypeId::of::<A>())
+    }
+}
+
+impl<A, E> Dispatcher<A> for Router<E>
+where
+    A: Clone + 'static,
+{
+    type Output = Result<(), E>;
+
+    /// Dispatches a clone of the action to every [`Store`] it is routed to, in order.
+    fn dispatch(&mut self, action: A) -> Self::Output {
+        if let Some(routes) = self.routes.get_mut(&TypeId::of::<A>()) {
+            for (index, route) in routes {
+                route(&mut *self.stores[*index], &action)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Registers the types of actions routed to a [`Store`] mounted on a [`Router`]
+/// (requires [`alloc`]).
+///
+/// [`alloc`]: index.html#optional-features
+pub struct Mount<'a, S, R, E> {
+    router: &'a mut Router<E>,
+    id: StoreId<S, R>,
+}
+
+impl<S, R, E> Debug for Mount<'_, S, R, E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Mount").field("id", &self.id).finish()
+    }
+}
+
+impl<S, R, E> Mount<'_, S, R, E>
+where
+    S: 'static,
+    R: 'static,
+{
+    /// Routes actions of type `A` to the [`Store`].
+    pub fn route<A>(self) -> Self
+    where
+        A: Clone + 'static,
+        S: Reducer<A>,
+        R: Reactor<S>,
+        R::Error: Into<E>,
+    {
+        let route: Route<E> = Box::new(|store, action| {
+            // A `Mount` only refers to a store mounted on its own router, under this very type,
+            // and the routing table only calls routes with actions of the type they're keyed by.
+            let store: &mut Store<S, R> = store.downcast_mut().unwrap();
+            let action: &A = action.downcast_ref().unwrap();
+            store.dispatch(action.clone()).map_err(Into::into)
+        });
+
+        self.router
+            .routes
+            .entry(TypeId::of::<A>())
+            .or_default()
+            .push((self.id.index, route));
+
+        self
+    }
+
+    /// Returns the [`StoreId`] of the mounted [`Store`].
+    pub fn id(&self) -> StoreId<S, R> {
+        self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reactor::MockReactor;
+    use crate::reducer::MockReducer;
+    use mockall::predicate::*;
+    use test_strategy::proptest;
+
+    type Mock = Store<MockReducer<u8>, MockReactor<MockReducer<u8>, u8>>;
+
+    #[proptest]
+    fn mount(a: u8, b: u8) {
+        let mut router = Router::<()>::new();
+        let x = router.mount(Store::new(a, ())).id();
+        let y = router.mount(Store::new(b, ())).id();
+
+        assert_ne!(x, y);
+        assert_eq!(router.get(x).map(|s| **s), Some(a));
+        assert_eq!(router.get(y).map(|s| **s), Some(b));
+        assert!(!router.is_routed::<u8>());
+
+        // Ids are only valid on the router that handed them out.
+        let mut other = Router::<()>::new();
+        let z = other.mount(Store::new(a, ())).id();
+        assert_ne!(x, z);
+        assert!(router.get(z).is_none());
+        assert!(other.get(x).is_none());
+        assert!(other.get(y).is_none());
+    }
+
+    #[proptest]
+    fn dispatch(action: u8, results: [Result<(), u8>; 3], unrouted: u16) {
+        let (idx, result) = results
+            .iter()
+            .enumerate()
+            .find(|(_, r)| r.is_err())
+            .map_or((results.len(), Ok(())), |(i, &r)| (i, r));
+
+        let mut router = Router::new();
+
+        for (i, &result) in results.iter().enumerate() {
+            let mut reducer = MockReducer::new();
+            reducer
+                .expect_reduce()
+                .with(eq(action))
+                .times(usize::from(i <= idx))
+                .return_const(());
+
+            let mut reactor = MockReactor::new();
+            reactor
+                .expect_react()
+                .times(usize::from(i <= idx))
+                .return_const(result);
+
+            let store: Mock = Store::new(reducer, reactor);
+            router.mount(store).route::<u8>();
+        }
+
+        assert!(router.is_routed::<u8>());
+        assert!(!router.is_routed::<u16>());
+        assert_eq!(router.dispatch(unrouted), Ok(()));
+        assert_eq!(router.dispatch(action), result);
+    }
+}
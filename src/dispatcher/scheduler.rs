@@ -0,0 +1,377 @@
+use crate::clock::Clock;
+use crate::dispatcher::DispatchError;
+use core::time::Duration;
+use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use futures::future::{AbortHandle, Abortable};
+use futures::sink::Sink;
+use futures::stream::{self, FusedStream, SelectAll, Stream, StreamExt};
+use std::boxed::Box;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+type Timer<A> = Abortable<Pin<Box<dyn Stream<Item = A> + Send>>>;
+
+/// A handle to an action scheduled through a [`Scheduler`] (requires [`async`]).
+///
+/// Dropping the handle does not cancel the scheduled action.
+///
+/// [`async`]: index.html#optional-features
+#[derive(Debug, Clone)]
+pub struct TimerHandle(AbortHandle);
+
+impl TimerHandle {
+    /// Cancels the scheduled action, unless it has already been dispatched.
+    pub fn cancel(&self) {
+        self.0.abort();
+    }
+}
+
+/// Dispatches actions at a later time (requires [`async`]).
+///
+/// [`Scheduler`] is a lightweight handle that can be cloned and sent to other threads.
+/// Scheduled actions are dispatched by a task that can be spawned onto an executor,
+/// which completes
+/// * successfully once all handles to the [`Scheduler`] are dropped and there are no
+///   pending scheduled actions, or as soon as the [`Scheduler`] is [shut down].
+/// * with an error if the underlying [`Dispatcher`](crate::Dispatcher) fails,
+///   e.g. because the [spawned] [`Store`](crate::Store) has terminated.
+///
+/// Either way, actions that are still pending when the task completes are discarded.
+///
+/// [shut down]: Scheduler::shutdown
+///
+/// [spawned]: crate::Store::into_task
+/// [`async`]: index.html#optional-features
+///
+/// # Example
+///
+/// ```rust
+/// use reducer::*;
+/// use futures::prelude::*;
+/// use std::error::Error;
+/// use std::time::{Duration, Instant};
+/// use tokio::task::spawn;
+///
+/// #[derive(Clone)]
+/// struct Tokio;
+///
+/// impl Clock for Tokio {
+///     type Sleep = tokio::time::Sleep;
+///
+///     fn now(&self) -> Instant {
+///         Instant::now()
+///     }
+///
+///     fn sleep(&self, duration: Duration) -> Self::Sleep {
+///         tokio::time::sleep(duration)
+///     }
+/// }
+///
+/// #[derive(Clone)]
+/// enum Action {
+///     Autosave,
+///     DismissToast,
+///     // ...
+/// }
+///
+/// #[derive(Clone)]
+/// struct State { /* ... */ }
+///
+/// impl Reducer<Action> for State {
+///     fn reduce(&mut self, action: Action) {
+///         // ...
+///     }
+/// }
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn Error>> {
+///     let store = Store::new(State { /* ... */ }, AsyncReactor(sink::drain()));
+///     let (task, mut dispatcher) = store.into_task();
+///     let handle = spawn(task);
+///
+///     // Scheduled actions are dispatched at low priority.
+///     let (timers, scheduler) = Scheduler::new(dispatcher.low(), Tokio);
+///     spawn(timers);
+///
+///     let autosave = scheduler.dispatch_every(Duration::from_secs(60), Action::Autosave)?;
+///     scheduler.dispatch_after(Duration::from_secs(5), Action::DismissToast)?;
+///
+///     // ...
+///
+///     autosave.cancel();
+///
+///     // Discard pending scheduled actions, so the store can terminate.
+///     scheduler.shutdown();
+///     dispatcher.close().await?;
+///     handle.await??;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Scheduler<A, C> {
+    timers: UnboundedSender<Timer<A>>,
+    shutdown: AbortHandle,
+    clock: C,
+}
+
+impl<A, C: Clone> Clone for Scheduler<A, C> {
+    fn clone(&self) -> Self {
+        Scheduler {
+            timers: self.timers.clone(),
+            shutdown: self.shutdown.clone(),
+            clock: self.clock.clone(),
+        }
+    }
+}
+
+impl<A, C> Scheduler<A, C> {
+    /// Discards all pending scheduled actions and completes the task that dispatches them.
+    ///
+    /// Further attempts to schedule actions fail with [`DispatchError::Terminated`].
+    pub fn shutdown(&self) {
+        self.timers.close_channel();
+        self.shutdown.abort();
+    }
+}
+
+impl<A, C> Scheduler<A, C>
+where
+    A: Send + 'static,
+    C: Clock + Clone + Send + 'static,
+    C::Sleep: Send,
+{
+    /// Constructs a [`Scheduler`] that dispatches actions through `dispatcher`, once `clock`
+    /// reaches the scheduled time, and returns it along with the task that does so.
+    pub fn new<D>(dispatcher: D, clock: C) -> (impl Future<Output = Result<(), D::Error>>, Self)
+    where
+        D: Sink<A>,
+    {
+        let (tx, rx) = unbounded();
+        let (shutdown, registration) = AbortHandle::new_pair();
+
+        let timers = Timers {
+            pending: rx,
+            active: SelectAll::new(),
+        };
+
+        let scheduler = Scheduler {
+            timers: tx,
+            shutdown,
+            clock,
+        };
+
+        let future = Abortable::new(timers, registration)
+            .map(Ok)
+            .forward(dispatcher);
+
+        (future, scheduler)
+    }
+
+    fn schedule<T>(&self, timer: T) -> Result<TimerHandle, DispatchError>
+    where
+        T: Stream<Item = A> + Send + 'static,
+    {
+        let (handle, registration) = AbortHandle::new_pair();
+        let timer: Pin<Box<dyn Stream<Item = A> + Send>> = Box::pin(timer);
+
+        self.timers
+            .unbounded_send(Abortable::new(timer, registration))
+            .map_err(|_| DispatchError::Terminated)?;
+
+        Ok(TimerHandle(handle))
+    }
+
+    /// Dispatches `action` once `delay` has elapsed.
+    pub fn dispatch_after(&self, delay: Duration, action: A) -> Result<TimerHandle, DispatchError> {
+        let clock = self.clock.clone();
+        let deadline = clock.now() + delay;
+
+        self.schedule(stream::once(async move {
+            clock
+                .sleep(deadline.saturating_duration_since(clock.now()))
+                .await;
+            action
+        }))
+    }
+
+    /// Dispatches `action` every `interval`, starting once `interval` has elapsed.
+    ///
+    /// Ticks are scheduled relative to the time of this call, ticks that have been missed,
+    /// e.g. because the dispatcher was not ready, are dispatched as soon as possible.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval` is zero.
+    pub fn dispatch_every(
+        &self,
+        interval: Duration,
+        action: A,
+    ) -> Result<TimerHandle, DispatchError>
+    where
+        A: Clone,
+    {
+        assert!(!interval.is_zero(), "`interval` must be non-zero");
+
+        let clock = self.clock.clone();
+        let deadline = clock.now() + interval;
+
+        self.schedule(stream::unfold(
+            (deadline, clock, action),
+            move |(deadline, clock, action)| async move {
+                clock
+                    .sleep(deadline.saturating_duration_since(clock.now()))
+                    .await;
+                Some((action.clone(), (deadline + interval, clock, action)))
+            },
+        ))
+    }
+}
+
+/// The stream of actions that are due.
+struct Timers<A> {
+    pending: UnboundedReceiver<Timer<A>>,
+    active: SelectAll<Timer<A>>,
+}
+
+impl<A> Stream for Timers<A> {
+    type Item = A;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        while !this.pending.is_terminated() {
+            match this.pending.poll_next_unpin(cx) {
+                Poll::Ready(Some(timer)) => this.active.push(timer),
+                Poll::Ready(None) | Poll::Pending => break,
+            }
+        }
+
+        match this.active.poll_next_unpin(cx) {
+            Poll::Ready(None) if !this.pending.is_terminated() => Poll::Pending,
+            poll => poll,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use futures::{channel::mpsc::unbounded, FutureExt};
+    use std::vec::Vec;
+    use test_strategy::proptest;
+    use tokio::runtime;
+
+    #[proptest]
+    fn dispatch_after(#[strategy(1u64..1000)] delay: u64, action: u8) {
+        let rt = runtime::Builder::new_multi_thread().build()?;
+        let clock = ManualClock::new();
+        let (tx, mut rx) = unbounded();
+        let (task, scheduler) = Scheduler::new(tx, clock.clone());
+        let handle = rt.spawn(task);
+
+        let delay = Duration::from_millis(delay);
+        scheduler.dispatch_after(delay, action)?;
+        drop(scheduler);
+
+        clock.advance(delay - Duration::from_millis(1));
+        assert_eq!(rx.next().now_or_never(), None);
+
+        clock.advance(Duration::from_millis(1));
+        assert_eq!(rt.block_on(rx.next()), Some(action));
+        assert_eq!(rt.block_on(handle)?, Ok(()));
+        assert_eq!(rt.block_on(rx.next()), None);
+    }
+
+    #[proptest]
+    fn dispatch_every(
+        #[strategy(1u64..1000)] interval: u64,
+        #[strategy(0usize..10)] ticks: usize,
+        action: u8,
+    ) {
+        let rt = runtime::Builder::new_multi_thread().build()?;
+        let clock = ManualClock::new();
+        let (tx, mut rx) = unbounded();
+        let (task, scheduler) = Scheduler::new(tx, clock.clone());
+        let handle = rt.spawn(task);
+
+        let interval = Duration::from_millis(interval);
+        let timer = scheduler.dispatch_every(interval, action)?;
+        drop(scheduler);
+
+        // Missed ticks are caught up on.
+        clock.advance(interval * ticks as u32);
+
+        for _ in 0..ticks {
+            assert_eq!(rt.block_on(rx.next()), Some(action));
+        }
+
+        timer.cancel();
+        assert_eq!(rt.block_on(handle)?, Ok(()));
+        assert_eq!(rt.block_on(rx.next()), None);
+    }
+
+    #[proptest]
+    fn cancel(delays: Vec<u16>, action: u8) {
+        let rt = runtime::Builder::new_multi_thread().build()?;
+        let clock = ManualClock::new();
+        let (tx, mut rx) = unbounded::<u8>();
+        let (task, scheduler) = Scheduler::new(tx, clock.clone());
+        let handle = rt.spawn(task);
+
+        for &delay in &delays {
+            let delay = Duration::from_millis(delay.into());
+            scheduler.dispatch_after(delay, action)?.cancel();
+        }
+
+        drop(scheduler);
+        clock.advance(Duration::from_millis(u16::MAX.into()));
+
+        assert_eq!(rt.block_on(handle)?, Ok(()));
+        assert_eq!(rt.block_on(rx.next()), None);
+    }
+
+    #[proptest]
+    fn shutdown(delays: Vec<u16>, action: u8) {
+        let rt = runtime::Builder::new_multi_thread().build()?;
+        let clock = ManualClock::new();
+        let (tx, mut rx) = unbounded::<u8>();
+        let (task, scheduler) = Scheduler::new(tx, clock.clone());
+        let handle = rt.spawn(task);
+
+        // Time never advances, so no action is ever due.
+        for &delay in &delays {
+            let delay = Duration::from_millis(u64::from(delay) + 1);
+            scheduler.dispatch_after(delay, action)?;
+        }
+
+        scheduler.clone().shutdown();
+
+        assert_eq!(rt.block_on(handle)?, Ok(()));
+        assert_eq!(rt.block_on(rx.next()), None);
+        assert_eq!(
+            scheduler.dispatch_after(Duration::ZERO, action).err(),
+            Some(DispatchError::Terminated)
+        );
+    }
+
+    #[proptest]
+    fn error(action: u8) {
+        let rt = runtime::Builder::new_multi_thread().build()?;
+        let clock = ManualClock::new();
+        let (tx, rx) = unbounded();
+        let (task, scheduler) = Scheduler::new(tx, clock.clone());
+        let handle = rt.spawn(task);
+
+        drop(rx);
+        scheduler.dispatch_after(Duration::ZERO, action)?;
+
+        assert!(rt.block_on(handle)?.is_err());
+        assert_eq!(
+            scheduler.dispatch_after(Duration::ZERO, action).err(),
+            Some(DispatchError::Terminated)
+        );
+    }
+}
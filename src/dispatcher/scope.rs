@@ -0,0 +1,392 @@
+use crate::dispatcher::{Dispatcher, Store};
+use crate::reactor::Reactor;
+use crate::reducer::Reducer;
+use core::ops::Deref;
+
+/// Trait for types that focus on a part of the state.
+///
+/// Lenses are how [`Scope`]s narrow the state of a [`Store`] down to a sub-state.
+pub trait Lens<S: ?Sized> {
+    /// The part of the state in focus.
+    type Target: ?Sized;
+
+    /// Borrows the part of the state in focus.
+    fn view<'s>(&self, state: &'s S) -> &'s Self::Target;
+
+    /// Mutably borrows the part of the state in focus.
+    fn view_mut<'s>(&self, state: &'s mut S) -> &'s mut Self::Target;
+}
+
+/// A [`Lens`] made out of a pair of accessor functions.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct FnLens<F, G> {
+    view: F,
+    view_mut: G,
+}
+
+impl<F, G> FnLens<F, G> {
+    /// Constructs a [`Lens`] given functions that borrow the part of the state in focus.
+    pub fn new<S, T>(view: F, view_mut: G) -> Self
+    where
+        S: ?Sized,
+        T: ?Sized,
+        F: Fn(&S) -> &T,
+        G: Fn(&mut S) -> &mut T,
+    {
+        FnLens { view, view_mut }
+    }
+}
+
+impl<S, T, F, G> Lens<S> for FnLens<F, G>
+where
+    S: ?Sized,
+    T: ?Sized,
+    F: Fn(&S) -> &T,
+    G: Fn(&mut S) -> &mut T,
+{
+    type Target = T;
+
+    fn view<'s>(&self, state: &'s S) -> &'s T {
+        (self.view)(state)
+    }
+
+    fn view_mut<'s>(&self, state: &'s mut S) -> &'s mut T {
+        (self.view_mut)(state)
+    }
+}
+
+/// A child [`Store`] scoped to a sub-state of its parent through a [`Lens`].
+///
+/// Actions dispatched on the [`Scope`] are reduced by the sub-state within the parent
+/// [`Store`], which remains the single source of truth.
+/// Upon every state transition, the [`Reactor`] of the [`Scope`] is notified of the sub-state,
+/// then the [`Reactor`] of the parent [`Store`] is notified of the entire state.
+///
+/// [`Scope`]s may in turn be scoped further.
+///
+/// # Example
+///
+/// ```rust
+/// use reducer::*;
+/// use std::convert::Infallible;
+///
+/// #[derive(Debug, Default)]
+/// struct Settings {
+///     theme: String,
+/// }
+///
+/// struct SetTheme(String);
+///
+/// impl Reducer<SetTheme> for Settings {
+///     fn reduce(&mut self, SetTheme(theme): SetTheme) {
+///         self.theme = theme;
+///     }
+/// }
+///
+/// #[derive(Debug, Default)]
+/// struct App {
+///     settings: Settings,
+///     // ...
+/// }
+///
+/// struct Log(Vec<String>);
+///
+/// impl<S: std::fmt::Debug> Reactor<S> for Log {
+///     type Error = Infallible;
+///     fn react(&mut self, state: &S) -> Result<(), Self::Error> {
+///         self.0.push(format!("{:?}", state));
+///         Ok(())
+///     }
+/// }
+///
+/// let mut store = Store::new(App::default(), Log(vec![]));
+///
+/// let lens = FnLens::new(|app: &App| &app.settings, |app: &mut App| &mut app.settings);
+/// let mut settings = store.scope(lens, Log(vec![]));
+///
+/// // Both the settings and the app get notified.
+/// settings.dispatch(SetTheme("dark".into())).unwrap();
+///
+/// assert_eq!(settings.theme, "dark");
+/// assert_eq!(settings.into_reactor().0, [r#"Settings { theme: "dark" }"#]);
+/// assert_eq!(store.settings.theme, "dark");
+/// ```
+#[derive(Debug)]
+pub struct Scope<P, L, R> {
+    parent: P,
+    lens: L,
+    reactor: R,
+}
+
+impl<S, R> Store<S, R> {
+    /// Creates a child [`Scope`] focused on the sub-state `lens` views,
+    /// with its own [`Reactor`].
+    pub fn scope<L, C>(&mut self, lens: L, reactor: C) -> Scope<&mut Self, L, C>
+    where
+        L: Lens<S>,
+    {
+        Scope {
+            parent: self,
+            lens,
+            reactor,
+        }
+    }
+}
+
+impl<P, L, R> Scope<P, L, R> {
+    /// Replaces the [`Reactor`] of the [`Scope`] and returns the previous one.
+    pub fn subscribe(&mut self, reactor: impl Into<R>) -> R {
+        core::mem::replace(&mut self.reactor, reactor.into())
+    }
+
+    /// Returns the [`Reactor`] of the [`Scope`].
+    pub fn into_reactor(self) -> R {
+        self.reactor
+    }
+
+    /// Creates a child [`Scope`] focused on a part of this [`Scope`]'s sub-state,
+    /// with its own [`Reactor`].
+    pub fn scope<M, C>(&mut self, lens: M, reactor: C) -> Scope<&mut Self, M, C>
+    where
+        Self: Deref,
+        M: Lens<<Self as Deref>::Target>,
+    {
+        Scope {
+            parent: self,
+            lens,
+            reactor,
+        }
+    }
+}
+
+use self::sealed::{Parent, Propagate};
+
+/// Implementation details of [`Scope`] that allow scopes to be nested.
+///
+/// The traits are public, so they may appear in the bounds of public impls,
+/// but they live in a private module, so they can be neither named nor implemented elsewhere.
+mod sealed {
+    /// Trait for types [`Scope`](super::Scope)s can be derived from.
+    pub trait Parent {
+        type State: ?Sized;
+
+        fn state(&self) -> &Self::State;
+    }
+
+    /// Trait for [`Parent`]s that propagate state transitions up the hierarchy.
+    pub trait Propagate: Parent {
+        type Error;
+
+        /// Applies `reduce` to the state and then notifies all reactors up the hierarchy.
+        fn update<F>(&mut self, reduce: F) -> Result<(), Self::Error>
+        where
+            F: FnOnce(&mut Self::State);
+    }
+}
+
+impl<S, R> Parent for &mut Store<S, R> {
+    type State = S;
+
+    fn state(&self) -> &S {
+        self
+    }
+}
+
+impl<S, R> Propagate for &mut Store<S, R>
+where
+    R: Reactor<S>,
+{
+    type Error = R::Error;
+
+    fn update<F>(&mut self, reduce: F) -> Result<(), R::Error>
+    where
+        F: FnOnce(&mut S),
+    {
+        let (state, reactor) = self.parts_mut();
+        reduce(state);
+        reactor.react(state)
+    }
+}
+
+impl<P, L, R> Parent for &mut Scope<P, L, R>
+where
+    P: Parent,
+    L: Lens<P::State>,
+{
+    type State = L::Target;
+
+    fn state(&self) -> &L::Target {
+        self.lens.view(self.parent.state())
+    }
+}
+
+impl<P, L, R> Propagate for &mut Scope<P, L, R>
+where
+    P: Propagate,
+    L: Lens<P::State>,
+    R: Reactor<L::Target>,
+    R::Error: Into<P::Error>,
+{
+    type Error = P::Error;
+
+    fn update<F>(&mut self, reduce: F) -> Result<(), P::Error>
+    where
+        F: FnOnce(&mut L::Target),
+    {
+        let Scope {
+            parent,
+            lens,
+            reactor,
+        } = &mut **self;
+
+        let mut child = Ok(());
+        let result = parent.update(|state| {
+            let state = lens.view_mut(state);
+            reduce(state);
+            child = reactor.react(state);
+        });
+
+        child.map_err(Into::into).and(result)
+    }
+}
+
+impl<P, L, R> Deref for Scope<P, L, R>
+where
+    P: Parent,
+    L: Lens<P::State>,
+{
+    type Target = L::Target;
+
+    fn deref(&self) -> &Self::Target {
+        self.lens.view(self.parent.state())
+    }
+}
+
+impl<A, P, L, R> Dispatcher<A> for Scope<P, L, R>
+where
+    P: Propagate,
+    L: Lens<P::State>,
+    L::Target: Reducer<A>,
+    R: Reactor<L::Target>,
+    R::Error: Into<P::Error>,
+{
+    type Output = Result<(), P::Error>;
+
+    /// Updates the sub-state via [`Reducer::reduce`] and notifies the [`Reactor`]s of this
+    /// [`Scope`] and all of its parents, returning the first error, if any.
+    fn dispatch(&mut self, action: A) -> Self::Output {
+        (&mut *self).update(|state| state.reduce(action))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reactor::MockReactor;
+    use crate::reducer::MockReducer;
+    use mockall::{predicate::*, Sequence};
+    use test_strategy::proptest;
+
+    #[proptest]
+    fn lens(mut state: (u8, u8), x: u8) {
+        let lens = FnLens::new(|s: &(u8, u8)| &s.1, |s: &mut (u8, u8)| &mut s.1);
+        assert_eq!(lens.view(&state), &state.1);
+
+        *lens.view_mut(&mut state) = x;
+        assert_eq!(state.1, x);
+    }
+
+    #[proptest]
+    fn deref(state: (u8, u8)) {
+        let mut store = Store::new(state, ());
+        let lens = FnLens::new(|s: &(u8, u8)| &s.1, |s: &mut (u8, u8)| &mut s.1);
+        let scope = store.scope(lens, ());
+        assert_eq!(*scope, state.1);
+    }
+
+    #[proptest]
+    fn subscribe(a: u8, b: u8) {
+        let mut store = Store::new((0u8,), ());
+        let lens = FnLens::new(|s: &(u8,)| &s.0, |s: &mut (u8,)| &mut s.0);
+        let mut scope = store.scope(lens, a);
+        assert_eq!(scope.subscribe(b), a);
+        assert_eq!(scope.into_reactor(), b);
+    }
+
+    type State = (MockReducer<u8>,);
+
+    #[proptest]
+    fn dispatch(action: u8, child: Result<(), u8>, parent: Result<(), u8>) {
+        let mut seq = Sequence::new();
+
+        let mut reducer = MockReducer::new();
+        reducer
+            .expect_reduce()
+            .with(eq(action))
+            .once()
+            .in_sequence(&mut seq)
+            .return_const(());
+
+        let mut reactor = MockReactor::<MockReducer<u8>, u8>::new();
+        reactor
+            .expect_react()
+            .once()
+            .in_sequence(&mut seq)
+            .return_const(child);
+
+        let mut parent_reactor = MockReactor::<State, u8>::new();
+        parent_reactor
+            .expect_react()
+            .once()
+            .in_sequence(&mut seq)
+            .return_const(parent);
+
+        let mut store = Store::new((reducer,), parent_reactor);
+        let lens = FnLens::new(|s: &State| &s.0, |s: &mut State| &mut s.0);
+        let mut scope = store.scope(lens, reactor);
+
+        assert_eq!(scope.dispatch(action), child.and(parent));
+    }
+
+    #[proptest]
+    fn nested(action: u8) {
+        let mut seq = Sequence::new();
+
+        let mut reducer = MockReducer::new();
+        reducer
+            .expect_reduce()
+            .with(eq(action))
+            .once()
+            .in_sequence(&mut seq)
+            .return_const(());
+
+        let mut grandchild = MockReactor::<MockReducer<u8>, u8>::new();
+        grandchild
+            .expect_react()
+            .once()
+            .in_sequence(&mut seq)
+            .return_const(Ok(()));
+
+        let mut child = MockReactor::<State, u8>::new();
+        child
+            .expect_react()
+            .once()
+            .in_sequence(&mut seq)
+            .return_const(Ok(()));
+
+        let mut parent = MockReactor::<(State,), u8>::new();
+        parent
+            .expect_react()
+            .once()
+            .in_sequence(&mut seq)
+            .return_const(Ok(()));
+
+        let mut store = Store::new(((reducer,),), parent);
+        let outer = FnLens::new(|s: &(State,)| &s.0, |s: &mut (State,)| &mut s.0);
+        let inner = FnLens::new(|s: &State| &s.0, |s: &mut State| &mut s.0);
+        let mut scope = store.scope(outer, child);
+        let mut scope = scope.scope(inner, grandchild);
+
+        assert_eq!(scope.dispatch(action), Ok(()));
+    }
+}
@@ -0,0 +1,326 @@
+use crate::dispatcher::{Dispatcher, Store};
+use crate::reactor::Reactor;
+use crate::reducer::Reducer;
+use core::mem::replace;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+const POISONED: &str = "a reducer panicked while dispatching an action";
+
+#[derive(Debug, Default)]
+struct Inner<S, R> {
+    state: RwLock<S>,
+    reactor: Mutex<R>,
+}
+
+/// A reactive state container that can be shared across threads (requires [`std`]).
+///
+/// [`SharedStore`] is a lightweight handle that can be cloned and sent to other threads,
+/// all clones refer to the same state and [`Reactor`].
+/// Just like [`Store`], the only way to mutate the state is by [dispatching] actions on it,
+/// but [`SharedStore`] only requires a shared reference to do so.
+///
+/// Actions are dispatched one at a time, while any number of threads may [read]
+/// the state concurrently, even while the [`Reactor`] is being notified.
+///
+/// Dispatching an action on a [`SharedStore`] from within its own [`Reactor`] deadlocks,
+/// use a [`DispatchQueue`](crate::DispatchQueue) to dispatch follow-up actions instead.
+///
+/// # Panics
+///
+/// If the [`Reducer`] panics, the state may be left partially updated,
+/// so the [`SharedStore`] is _poisoned_ and every subsequent attempt to
+/// [read] the state or to [dispatch] actions on it panics too.
+///
+/// The [`Reactor`] is only notified once the state is fully updated,
+/// so the [`SharedStore`] remains usable if the [`Reactor`] panics.
+///
+/// [`std`]: index.html#optional-features
+/// [dispatching]: SharedStore::dispatch
+/// [dispatch]: SharedStore::dispatch
+/// [read]: SharedStore::read
+///
+/// # Example
+///
+/// ```rust
+/// use reducer::*;
+/// use std::thread;
+///
+/// #[derive(Debug, Default)]
+/// struct Counter(u32);
+///
+/// struct Increment;
+///
+/// impl Reducer<Increment> for Counter {
+///     fn reduce(&mut self, _: Increment) {
+///         self.0 += 1;
+///     }
+/// }
+///
+/// struct Nop;
+///
+/// impl<S> Reactor<S> for Nop {
+///     type Error = std::convert::Infallible;
+///     fn react(&mut self, _: &S) -> Result<(), Self::Error> {
+///         Ok(())
+///     }
+/// }
+///
+/// let store = SharedStore::new(Counter::default(), Nop);
+///
+/// let workers: Vec<_> = (0..4)
+///     .map(|_| {
+///         let store = store.clone();
+///         thread::spawn(move || {
+///             for _ in 0..100 {
+///                 store.dispatch(Increment).unwrap();
+///             }
+///         })
+///     })
+///     .collect();
+///
+/// for worker in workers {
+///     worker.join().unwrap();
+/// }
+///
+/// assert_eq!(store.read().0, 400);
+/// ```
+#[derive(Debug, Default)]
+pub struct SharedStore<S, R> {
+    inner: Arc<Inner<S, R>>,
+}
+
+impl<S, R> Clone for SharedStore<S, R> {
+    fn clone(&self) -> Self {
+        SharedStore {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<S, R> From<Store<S, R>> for SharedStore<S, R> {
+    fn from(store: Store<S, R>) -> Self {
+        let (state, reactor) = store.into_parts();
+        Self::new(state, reactor)
+    }
+}
+
+impl<S, R> SharedStore<S, R> {
+    /// Constructs the SharedStore given the initial state and a [`Reactor`].
+    pub fn new(state: S, reactor: R) -> Self {
+        SharedStore {
+            inner: Arc::new(Inner {
+                state: RwLock::new(state),
+                reactor: Mutex::new(reactor),
+            }),
+        }
+    }
+
+    /// Locks the state for reading.
+    ///
+    /// Actions dispatched while the guard is held block until it's dropped.
+    ///
+    /// # Panics
+    ///
+    /// If the [`SharedStore`] is poisoned.
+    pub fn read(&self) -> RwLockReadGuard<'_, S> {
+        self.inner.state.read().expect(POISONED)
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, S> {
+        self.inner.state.write().expect(POISONED)
+    }
+
+    fn reactor(&self) -> MutexGuard<'_, R> {
+        // The state is consistent even if the reactor panicked.
+        self.inner
+            .reactor
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Replaces the [`Reactor`] and returns the previous one.
+    pub fn subscribe(&self, reactor: impl Into<R>) -> R {
+        replace(&mut *self.reactor(), reactor.into())
+    }
+
+    /// Updates the state via [`Reducer::reduce`] and notifies the [`Reactor`],
+    /// returning the result of calling [`Reactor::react`] with a reference
+    /// to the new state.
+    ///
+    /// # Panics
+    ///
+    /// If the [`SharedStore`] is poisoned.
+    pub fn dispatch<A>(&self, action: A) -> Result<(), R::Error>
+    where
+        S: Reducer<A>,
+        R: Reactor<S>,
+    {
+        // Holding on to the reactor serializes dispatches.
+        let mut reactor = self.reactor();
+        self.write().reduce(action);
+        reactor.react(&self.read())
+    }
+}
+
+impl<A, S, R> Dispatcher<A> for SharedStore<S, R>
+where
+    S: Reducer<A>,
+    R: Reactor<S>,
+{
+    type Output = Result<(), R::Error>;
+
+    /// See [`SharedStore::dispatch`].
+    fn dispatch(&mut self, action: A) -> Self::Output {
+        SharedStore::dispatch(self, action)
+    }
+}
+
+impl<A, S, R> Dispatcher<A> for &SharedStore<S, R>
+where
+    S: Reducer<A>,
+    R: Reactor<S>,
+{
+    type Output = Result<(), R::Error>;
+
+    /// See [`SharedStore::dispatch`].
+    fn dispatch(&mut self, action: A) -> Self::Output {
+        SharedStore::dispatch(self, action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reactor::MockReactor;
+    use crate::reducer::MockReducer;
+    use mockall::predicate::*;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::{thread, vec::Vec};
+    use test_strategy::proptest;
+
+    #[proptest]
+    fn default() {
+        SharedStore::<(), ()>::default();
+    }
+
+    #[proptest]
+    fn new(state: u8, reactor: u8) {
+        let store = SharedStore::new(state, reactor);
+        assert_eq!(*store.read(), state);
+        assert_eq!(*store.reactor(), reactor);
+    }
+
+    #[proptest]
+    fn from(state: u8, reactor: u8) {
+        let store = SharedStore::from(Store::new(state, reactor));
+        assert_eq!(*store.read(), state);
+        assert_eq!(*store.reactor(), reactor);
+    }
+
+    #[proptest]
+    fn clone(state: u8, a: u8, b: u8) {
+        let store = SharedStore::new(state, a);
+        let other = store.clone();
+        assert_eq!(other.subscribe(b), a);
+        assert_eq!(*store.reactor(), b);
+    }
+
+    #[proptest]
+    fn dispatch(action: u8, result: Result<(), u8>, id: usize) {
+        let mut reducer = MockReducer::new();
+        reducer.expect_id().return_const(id);
+        reducer.expect_clone().never();
+        reducer
+            .expect_reduce()
+            .with(eq(action))
+            .once()
+            .return_const(());
+
+        let mut reactor = MockReactor::new();
+        reactor
+            .expect_react()
+            .with(function(move |x: &MockReducer<_>| x.id() == id))
+            .once()
+            .return_const(result);
+
+        let mut store = SharedStore::new(reducer, reactor);
+        assert_eq!(Dispatcher::dispatch(&mut store, action), result);
+    }
+
+    #[proptest]
+    fn poison(action: u8) {
+        let mut reducer = MockReducer::new();
+        reducer
+            .expect_reduce()
+            .once()
+            .returning(|_| panic!("reducer panicked"));
+
+        let store = SharedStore::new(reducer, MockReactor::<_, ()>::new());
+        let other = store.clone();
+
+        assert!(thread::spawn(move || other.dispatch(action))
+            .join()
+            .is_err());
+        assert!(panic::catch_unwind(AssertUnwindSafe(|| store.read())).is_err());
+        assert!(panic::catch_unwind(AssertUnwindSafe(|| store.dispatch(action))).is_err());
+    }
+
+    #[proptest]
+    fn reactor_panic(action: u8, result: Result<(), u8>) {
+        let mut reducer = MockReducer::new();
+        reducer.expect_reduce().times(2).return_const(());
+
+        let mut reactor = MockReactor::new();
+        reactor
+            .expect_react()
+            .once()
+            .returning(|_| panic!("reactor panicked"));
+        reactor.expect_react().once().return_const(result);
+
+        let store = SharedStore::new(reducer, reactor);
+        let other = store.clone();
+
+        assert!(thread::spawn(move || other.dispatch(action))
+            .join()
+            .is_err());
+
+        // The state is not poisoned.
+        drop(store.read());
+        assert_eq!(store.dispatch(action), result);
+    }
+
+    #[proptest]
+    fn concurrent(#[strategy(1usize..8)] threads: usize, actions: Vec<u8>) {
+        let mut reducer = MockReducer::new();
+        reducer
+            .expect_reduce()
+            .times(2 * threads * actions.len())
+            .return_const(());
+
+        let mut reactor = MockReactor::new();
+        reactor
+            .expect_react()
+            .times(2 * threads * actions.len())
+            .return_const(Ok::<(), ()>(()));
+
+        let store = SharedStore::new(reducer, reactor);
+
+        let handles: Vec<_> = (0..threads)
+            .map(|_| {
+                let mut store = store.clone();
+                let actions = actions.clone();
+                thread::spawn(move || {
+                    for action in actions {
+                        drop(store.read());
+                        assert_eq!(Dispatcher::dispatch(&mut &store, action), Ok(()));
+                        assert_eq!(Dispatcher::dispatch(&mut store, action), Ok(()));
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use crate::dispatcher::*;
+use alloc::vec::Vec;
+
+/// Dispatches a clone of the action to all [`Dispatcher`]s in the slice in order,
+/// collecting their outputs (requires [`alloc`]).
+///
+/// [`alloc`]: index.html#optional-features
+///
+/// # Example
+///
+/// ```rust
+/// use reducer::*;
+///
+/// #[derive(Clone)]
+/// struct Action { /* ... */ }
+///
+/// struct Channel { /* ... */ }
+///
+/// impl Dispatcher<Action> for Channel {
+///     type Output = bool;
+///     fn dispatch(&mut self, action: Action) -> Self::Output {
+///         // ...
+///         true
+///     }
+/// }
+///
+/// let mut channels = vec![];
+///
+/// channels.push(Channel { /* ... */ });
+/// channels.push(Channel { /* ... */ });
+/// // ...
+/// channels.push(Channel { /* ... */ });
+///
+/// let mut channels = channels.into_boxed_slice();
+///
+/// // All channels receive the action.
+/// let outputs: Vec<bool> = channels.dispatch(Action { /* ... */ });
+/// ```
+impl<A, D> Dispatcher<A> for [D]
+where
+    A: Clone,
+    D: Dispatcher<A>,
+{
+    type Output = Vec<D::Output>;
+
+    fn dispatch(&mut self, action: A) -> Self::Output {
+        self.iter_mut()
+            .map(|d| d.dispatch(action.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockall::{predicate::*, Sequence};
+    use test_strategy::proptest;
+
+    #[proptest]
+    fn dispatch(action: u8, results: Vec<u8>) {
+        let mut seq = Sequence::new();
+
+        let mut mocks: Vec<_> = results
+            .iter()
+            .map(|&result| {
+                let mut mock = MockDispatcher::<u8, u8>::new();
+
+                mock.expect_dispatch()
+                    .with(eq(action))
+                    .once()
+                    .in_sequence(&mut seq)
+                    .return_const(result);
+
+                mock
+            })
+            .collect();
+
+        let dispatcher = mocks.as_mut_slice();
+        assert_eq!(Dispatcher::dispatch(dispatcher, action), results);
+    }
+}
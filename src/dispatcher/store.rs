@@ -1,6 +1,7 @@
 use crate::dispatcher::Dispatcher;
 use crate::reactor::Reactor;
 use crate::reducer::Reducer;
+use crate::trace;
 use core::mem::replace;
 use derive_more::Deref;
 
@@ -95,6 +96,37 @@ impl<S, R> Store<S, R> {
     pub fn subscribe(&mut self, reactor: impl Into<R>) -> R {
         replace(&mut self.reactor, reactor.into())
     }
+
+    /// Dispatches an action just like [`Store::dispatch`], but also records the `Debug` output
+    /// of the action and of the error, if any, in the trace (requires [`tracing`]).
+    ///
+    /// [`Store::dispatch`] can only record type names, since not every action implements `Debug`.
+    ///
+    /// [`tracing`]: index.html#optional-features
+    #[cfg(feature = "tracing")]
+    pub fn dispatch_debug<A>(&mut self, action: A) -> Result<(), R::Error>
+    where
+        A: core::fmt::Debug,
+        S: Reducer<A>,
+        R: Reactor<S>,
+        R::Error: core::fmt::Debug,
+    {
+        trace::dispatch_debug(&action).in_scope(|| {
+            trace::reduce().in_scope(|| self.state.reduce(action));
+            trace::react()
+                .in_scope(|| self.reactor.react(&self.state))
+                .inspect_err(trace::failed_debug)
+        })
+    }
+
+    pub(crate) fn parts_mut(&mut self) -> (&mut S, &mut R) {
+        (&mut self.state, &mut self.reactor)
+    }
+
+    #[cfg(feature = "alloc")]
+    pub(crate) fn into_parts(self) -> (S, R) {
+        (self.state, self.reactor)
+    }
 }
 
 impl<A, S, R> Dispatcher<A> for Store<S, R>
@@ -108,21 +140,31 @@ where
     /// returning the result of calling [`Reactor::react`] with a reference
     /// to the new state.
     fn dispatch(&mut self, action: A) -> Self::Output {
-        self.state.reduce(action);
-        self.reactor.react(&self.state)
+        trace::dispatch::<A>().in_scope(|| {
+            trace::reduce().in_scope(|| self.state.reduce(action));
+            trace::react()
+                .in_scope(|| self.reactor.react(&self.state))
+                .inspect_err(|_| trace::failed::<R::Error>())
+        })
     }
 }
 
 #[cfg(feature = "async")]
 mod sink {
     use super::*;
-    use crate::dispatcher::AsyncDispatcher;
+    use crate::dispatcher::watcher::{watch, Publisher};
+    use crate::dispatcher::{StateWatcher, TaskDispatcher};
+    use crate::reducer::{EffectReducer, Effects};
+    use crate::trace::Instrument;
     use derive_more::{Display, Error};
-    use futures::channel::mpsc::channel;
+    use futures::future::{self, Either};
     use futures::prelude::*;
-    use futures::sink::Sink;
+    use futures::stream::{Fuse, FusedStream};
+    use futures::{pin_mut, sink::Sink};
     use std::pin::Pin;
+    use std::sync::Arc;
     use std::task::{Context, Poll};
+    use std::vec::Vec;
 
     /// View Store as a Sink of actions (requires [`async`]).
     ///
@@ -140,8 +182,13 @@ mod sink {
 
         fn start_send(self: Pin<&mut Self>, action: A) -> Result<(), Self::Error> {
             let PinnedStore { state, reactor } = self.project();
-            state.reduce(action);
-            reactor.start_send(state)
+
+            trace::dispatch::<A>().in_scope(|| {
+                trace::reduce().in_scope(|| state.reduce(action));
+                trace::react()
+                    .in_scope(|| reactor.start_send(state))
+                    .inspect_err(|_| trace::failed::<E>())
+            })
         }
 
         fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
@@ -172,10 +219,11 @@ mod sink {
         /// (requires [`async`]).
         ///
         /// Once spawned, the task will receive actions dispatched through a lightweight
-        /// [`Dispatcher`] handle that can be cloned and sent to other threads.
+        /// [`TaskDispatcher`] handle that can be cloned and sent to other threads.
+        /// Actions may be dispatched at different [priorities](crate::Priority).
         ///
         /// The task completes
-        /// * successfully if the asynchronous [`Dispatcher`] (or the last of its clones)
+        /// * successfully if the [`TaskDispatcher`] (or the last of its clones)
         ///   is dropped or [closed].
         /// * with an error if [`Store::dispatch`] fails.
         ///
@@ -246,24 +294,530 @@ mod sink {
         ///     Ok(())
         /// }
         /// ```
-        pub fn into_task<A, E>(
+        pub fn into_task<A, E>(self) -> (impl Future<Output = Result<(), E>>, TaskDispatcher<A>)
+        where
+            Self: Sink<A, Error = E>,
+        {
+            spawn(self)
+        }
+
+        /// Turns the [`Store`] into a task that can be spawned onto an executor, much like
+        /// [`Store::into_task`], but also returns a [`StateWatcher`] that observes the latest
+        /// state (requires [`async`]).
+        ///
+        /// The watcher is notified after every state transition and yields
+        /// the current state to new consumers immediately.
+        ///
+        /// Publishing clones the state after every transition, unless all watchers have been
+        /// dropped, so large states are best wrapped in an [`Arc`], which the [`Store`] updates
+        /// with copy-on-write semantics and which is cheap to clone.
+        ///
+        /// [`async`]: index.html#optional-features
+        ///
+        /// # Example
+        ///
+        /// ```rust
+        /// use reducer::*;
+        /// use futures::prelude::*;
+        /// use std::error::Error;
+        /// use tokio::task::spawn;
+        ///
+        /// #[derive(Clone)]
+        /// struct Counter(i32);
+        ///
+        /// struct Increment;
+        ///
+        /// impl Reducer<Increment> for Counter {
+        ///     fn reduce(&mut self, _: Increment) {
+        ///         self.0 += 1;
+        ///     }
+        /// }
+        ///
+        /// #[tokio::main]
+        /// async fn main() -> Result<(), Box<dyn Error>> {
+        ///     let store = Store::new(Counter(0), AsyncReactor(sink::drain()));
+        ///
+        ///     let (task, mut dispatcher, mut watcher) = store.into_watched_task();
+        ///     let handle = spawn(task);
+        ///
+        ///     // The current state is available immediately.
+        ///     assert_eq!(watcher.next().await.map(|s| s.0), Some(0));
+        ///
+        ///     dispatcher.send(Increment).await?;
+        ///     dispatcher.send(Increment).await?;
+        ///     dispatcher.close().await?;
+        ///     handle.await??;
+        ///
+        ///     // Intermediate states are skipped.
+        ///     assert_eq!(watcher.next().await.map(|s| s.0), Some(2));
+        ///
+        ///     // The watcher terminates along with the task.
+        ///     assert_eq!(watcher.next().await.map(|s| s.0), None);
+        ///
+        ///     Ok(())
+        /// }
+        /// ```
+        pub fn into_watched_task<A, E>(
             self,
         ) -> (
             impl Future<Output = Result<(), E>>,
-            impl Dispatcher<A, Output = Result<(), DispatchError>>
-                + Sink<A, Error = DispatchError>
-                + Clone,
+            TaskDispatcher<A>,
+            StateWatcher<S>,
         )
         where
             Self: Sink<A, Error = E>,
+            S: Clone,
         {
-            let (tx, rx) = channel(0);
-            let future = rx.map(Ok).forward(self);
-            let dispatcher = AsyncDispatcher(tx.sink_map_err(|_| DispatchError::Terminated));
+            let (publisher, watcher) = watch(Arc::new(self.state.clone()));
+            let (future, dispatcher) = spawn(Watched {
+                store: self,
+                publisher,
+            });
+
+            (future, dispatcher, watcher)
+        }
+    }
+
+    impl<S, R> Store<S, R> {
+        /// Turns the [`Store`] into a task that can be spawned onto an executor and that
+        /// executes the [`Effect`](crate::Effect)s returned by [`EffectReducer::reduce`]
+        /// (requires [`async`]).
+        ///
+        /// Much like [`Store::into_task`], the task receives actions through a
+        /// [`TaskDispatcher`], but it also receives the actions produced by running effects.
+        ///
+        /// The task completes
+        /// * successfully once the [`TaskDispatcher`] (or the last of its clones)
+        ///   is dropped or [closed] and all effects have finished running.
+        /// * with an error if notifying the [`Reactor`] fails.
+        ///
+        /// [`async`]: index.html#optional-features
+        /// [closed]: futures::sink::SinkExt::close
+        ///
+        /// # Example
+        ///
+        /// ```rust
+        /// use reducer::*;
+        /// use futures::prelude::*;
+        /// use std::error::Error;
+        /// use tokio::task::spawn;
+        ///
+        /// #[derive(Clone, Default)]
+        /// struct Session {
+        ///     user: Option<String>,
+        ///     profile: Option<String>,
+        /// }
+        ///
+        /// enum Action {
+        ///     LoggedIn(String),
+        ///     ProfileLoaded(String),
+        /// }
+        ///
+        /// impl EffectReducer<Action> for Session {
+        ///     fn reduce(&mut self, action: Action) -> Effect<Action> {
+        ///         match action {
+        ///             Action::LoggedIn(user) => {
+        ///                 self.user = Some(user.clone());
+        ///
+        ///                 // Load the profile once logged in.
+        ///                 Effect::future(async move {
+        ///                     Action::ProfileLoaded(format!("{}'s profile", user))
+        ///                 })
+        ///             }
+        ///
+        ///             Action::ProfileLoaded(profile) => {
+        ///                 self.profile = Some(profile);
+        ///                 Effect::None
+        ///             }
+        ///         }
+        ///     }
+        /// }
+        ///
+        /// #[tokio::main]
+        /// async fn main() -> Result<(), Box<dyn Error>> {
+        ///     let (tx, rx) = futures::channel::mpsc::unbounded();
+        ///     let store = Store::new(Session::default(), AsyncReactor(tx));
+        ///
+        ///     let (task, mut dispatcher) = store.into_effect_task();
+        ///     let handle = spawn(task);
+        ///
+        ///     dispatcher.send(Action::LoggedIn("Alice".into())).await?;
+        ///     dispatcher.close().await?;
+        ///
+        ///     // The task completes once the profile is loaded.
+        ///     handle.await??;
+        ///
+        ///     let states: Vec<Session> = rx.collect().await;
+        ///     assert_eq!(states.last().unwrap().profile.as_deref(), Some("Alice's profile"));
+        ///
+        ///     Ok(())
+        /// }
+        /// ```
+        pub fn into_effect_task<A, E>(
+            self,
+        ) -> (impl Future<Output = Result<(), E>>, TaskDispatcher<A>)
+        where
+            S: EffectReducer<A>,
+            R: for<'s> Sink<&'s S, Error = E>,
+            A: Send + 'static,
+        {
+            let (dispatcher, actions) = TaskDispatcher::new();
+
+            let future = async move {
+                let mut effects = Effects::new(actions);
+                let store = self;
+                pin_mut!(store);
+
+                while let Some(action) = effects.next().await {
+                    let PinnedStore { state, mut reactor } = store.as_mut().project();
+
+                    let span = trace::dispatch::<A>();
+                    let effect =
+                        span.in_scope(|| trace::reduce().in_scope(|| state.reduce(action)));
+                    effects.run(effect);
+
+                    let react = span.in_scope(trace::react);
+                    if let Err(e) = reactor.send(state).instrument(react).await {
+                        span.in_scope(trace::failed::<E>);
+                        return Err(e);
+                    }
+                }
+
+                store.project().reactor.close().await
+            };
 
             (future, dispatcher)
         }
     }
+
+    impl<S, R> Store<S, R> {
+        /// Turns the [`Store`] into a task that can be spawned onto an executor, much like
+        /// [`Store::into_task`], but that merges consecutive actions through `merge`
+        /// (requires [`async`]).
+        ///
+        /// Whenever the [`Reactor`] is busy, i.e. it's not ready to be notified of the next
+        /// state, the task keeps receiving actions and merges them, rather than holding back
+        /// the producer until they can be dispatched one at a time.
+        /// `merge` returns the action equivalent to dispatching both, or hands them back if
+        /// they can't be merged, see [`DispatcherExt::coalesce`](crate::DispatcherExt::coalesce).
+        ///
+        /// [`async`]: index.html#optional-features
+        ///
+        /// # Example
+        ///
+        /// ```rust
+        /// use reducer::*;
+        /// use futures::prelude::*;
+        /// use std::error::Error;
+        /// use tokio::task::spawn;
+        ///
+        /// #[derive(Debug, Clone, Default, PartialEq)]
+        /// struct Cursor(i32, i32);
+        ///
+        /// struct MoveTo(i32, i32);
+        ///
+        /// impl Reducer<MoveTo> for Cursor {
+        ///     fn reduce(&mut self, MoveTo(x, y): MoveTo) {
+        ///         *self = Cursor(x, y);
+        ///     }
+        /// }
+        ///
+        /// #[tokio::main(flavor = "current_thread")]
+        /// async fn main() -> Result<(), Box<dyn Error>> {
+        ///     // The reactor is busy until the state it was notified of is received.
+        ///     let (tx, rx) = futures::channel::mpsc::channel(0);
+        ///     let store = Store::new(Cursor::default(), AsyncReactor(tx));
+        ///
+        ///     // Only the latest position matters.
+        ///     let (task, mut dispatcher) = store.into_coalesced_task(|_, last| Ok(last));
+        ///     let handle = spawn(task);
+        ///
+        ///     for i in 0..100 {
+        ///         dispatcher.send(MoveTo(i, i)).await?;
+        ///     }
+        ///
+        ///     dispatcher.close().await?;
+        ///     let states: Vec<Cursor> = rx.collect().await;
+        ///     handle.await??;
+        ///
+        ///     // The moves sent while the reactor was busy are merged into one.
+        ///     assert_eq!(states, [Cursor(0, 0), Cursor(99, 99)]);
+        ///
+        ///     Ok(())
+        /// }
+        /// ```
+        pub fn into_coalesced_task<A, E, F>(
+            self,
+            merge: F,
+        ) -> (impl Future<Output = Result<(), E>>, TaskDispatcher<A>)
+        where
+            Self: Sink<A, Error = E>,
+            F: FnMut(A, A) -> Result<A, (A, A)>,
+        {
+            let (dispatcher, actions) = TaskDispatcher::new();
+
+            let future = Coalesced {
+                actions: actions.fuse(),
+                store: self,
+                merge,
+                pending: None,
+                blocked: None,
+            };
+
+            (future, dispatcher)
+        }
+    }
+
+    /// How a [hydrating] [`Store`] handles actions dispatched before its state is loaded
+    /// (requires [`async`]).
+    ///
+    /// [hydrating]: Store::into_hydrated_task
+    /// [`async`]: index.html#optional-features
+    #[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+    pub enum Hydration {
+        /// Actions are held back and only reduced once the state is loaded.
+        #[default]
+        Queue,
+
+        /// Actions are reduced on the initial state right away,
+        /// then reduced again on top of the loaded state.
+        Replay,
+    }
+
+    impl<S, R> Store<S, R> {
+        /// Turns the [`Store`] into a task that can be spawned onto an executor, much like
+        /// [`Store::into_task`], but that replaces the state once `state` resolves
+        /// (requires [`async`]).
+        ///
+        /// This makes it possible to start dispatching actions right away,
+        /// while the state is loaded asynchronously, e.g. from disk.
+        /// Until then, actions are handled as configured by [`Hydration`],
+        /// and once the state is loaded, pending actions are reduced on top of it
+        /// before the [`Reactor`] is notified.
+        ///
+        /// [`async`]: index.html#optional-features
+        ///
+        /// # Example
+        ///
+        /// ```rust
+        /// use reducer::*;
+        /// use futures::channel::{mpsc, oneshot};
+        /// use futures::prelude::*;
+        /// use std::error::Error;
+        /// use tokio::task::spawn;
+        ///
+        /// #[derive(Debug, Default, Clone, PartialEq)]
+        /// struct Todos(Vec<String>);
+        ///
+        /// #[derive(Clone)]
+        /// struct Add(String);
+        ///
+        /// impl Reducer<Add> for Todos {
+        ///     fn reduce(&mut self, Add(todo): Add) {
+        ///         self.0.push(todo);
+        ///     }
+        /// }
+        ///
+        /// #[tokio::main]
+        /// async fn main() -> Result<(), Box<dyn Error>> {
+        ///     let (tx, mut rx) = mpsc::unbounded();
+        ///     let store = Store::new(Todos::default(), AsyncReactor(tx));
+        ///
+        ///     // Stands for reading the state from disk.
+        ///     let (loaded, load) = oneshot::channel();
+        ///
+        ///     let (task, mut dispatcher) =
+        ///         store.into_hydrated_task(load.map(Result::unwrap), Hydration::Queue);
+        ///
+        ///     let handle = spawn(task);
+        ///
+        ///     // Actions may be dispatched before the state is loaded.
+        ///     dispatcher.send(Add("fix bugs".into())).await?;
+        ///
+        ///     loaded.send(Todos(vec!["write docs".into()])).unwrap();
+        ///
+        ///     // The reactor is notified once the pending action is reduced on the loaded state.
+        ///     let todos = rx.next().await.unwrap();
+        ///     assert_eq!(todos.0, ["write docs", "fix bugs"]);
+        ///
+        ///     dispatcher.close().await?;
+        ///     handle.await??;
+        ///
+        ///     Ok(())
+        /// }
+        /// ```
+        pub fn into_hydrated_task<A, E, F>(
+            self,
+            state: F,
+            hydration: Hydration,
+        ) -> (impl Future<Output = Result<(), E>>, TaskDispatcher<A>)
+        where
+            S: Reducer<A>,
+            R: for<'s> Sink<&'s S, Error = E>,
+            A: Clone,
+            F: Future<Output = S>,
+        {
+            let (dispatcher, actions) = TaskDispatcher::<A>::new();
+
+            let future = async move {
+                let store = self;
+                pin_mut!(store, state, actions);
+
+                let mut pending = Vec::new();
+
+                let hydrated = loop {
+                    match future::select(state.as_mut(), actions.next()).await {
+                        Either::Left((hydrated, _)) => break hydrated,
+                        Either::Right((None, state)) => break state.await,
+                        Either::Right((Some(action), _)) => match hydration {
+                            Hydration::Queue => pending.push(action),
+                            Hydration::Replay => {
+                                pending.push(action.clone());
+                                store.as_mut().send(action).await?;
+                            }
+                        },
+                    }
+                };
+
+                let PinnedStore { state, mut reactor } = store.as_mut().project();
+                *state = hydrated;
+
+                for action in pending {
+                    trace::dispatch::<A>()
+                        .in_scope(|| trace::reduce().in_scope(|| state.reduce(action)));
+                }
+
+                reactor.send(state).await?;
+                actions.map(Ok).forward(store).await
+            };
+
+            (future, dispatcher)
+        }
+    }
+
+    /// Dispatches actions on the [`Store`], merging those received while it's busy.
+    #[pin_project]
+    struct Coalesced<St, K, A, F> {
+        #[pin]
+        actions: Fuse<St>,
+        #[pin]
+        store: K,
+        merge: F,
+        pending: Option<A>,
+        blocked: Option<A>,
+    }
+
+    impl<St, K, A, F, E> Future for Coalesced<St, K, A, F>
+    where
+        St: Stream<Item = A>,
+        K: Sink<A, Error = E>,
+        F: FnMut(A, A) -> Result<A, (A, A)>,
+    {
+        type Output = Result<(), E>;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            let mut this = self.project();
+
+            loop {
+                // Receive actions until one can't be merged into the pending one.
+                while this.blocked.is_none() {
+                    let action = match this.actions.as_mut().poll_next(cx) {
+                        Poll::Ready(Some(action)) => action,
+                        Poll::Ready(None) | Poll::Pending => break,
+                    };
+
+                    *this.pending = match this.pending.take() {
+                        None => Some(action),
+                        Some(previous) => match (this.merge)(previous, action) {
+                            Ok(merged) => Some(merged),
+                            Err((previous, action)) => {
+                                *this.blocked = Some(action);
+                                Some(previous)
+                            }
+                        },
+                    };
+                }
+
+                let action = match this.pending.take() {
+                    Some(action) => action,
+                    None if this.actions.is_terminated() => return this.store.poll_close(cx),
+                    None => {
+                        return match this.store.poll_flush(cx) {
+                            Poll::Ready(Err(e)) => Poll::Ready(Err(e)),
+                            _ => Poll::Pending,
+                        }
+                    }
+                };
+
+                match this.store.as_mut().poll_ready(cx) {
+                    Poll::Ready(Ok(())) => this.store.as_mut().start_send(action)?,
+                    Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                    Poll::Pending => {
+                        // Actions received in the meantime are merged into this one.
+                        *this.pending = Some(action);
+                        return Poll::Pending;
+                    }
+                }
+
+                *this.pending = this.blocked.take();
+            }
+        }
+    }
+
+    /// Publishes the state of the [`Store`] after every transition.
+    #[pin_project]
+    struct Watched<S, R> {
+        #[pin]
+        store: Store<S, R>,
+        publisher: Publisher<S>,
+    }
+
+    impl<A, S, R, E> Sink<A> for Watched<S, R>
+    where
+        S: Clone,
+        Store<S, R>: Sink<A, Error = E>,
+    {
+        type Error = E;
+
+        fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            self.project().store.poll_ready(cx)
+        }
+
+        fn start_send(self: Pin<&mut Self>, action: A) -> Result<(), Self::Error> {
+            let mut this = self.project();
+            let result = this.store.as_mut().start_send(action);
+
+            if this.publisher.is_watched() {
+                this.publisher.publish(Arc::new(this.store.state.clone()));
+            }
+
+            result
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            self.project().store.poll_flush(cx)
+        }
+
+        fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            self.project().store.poll_close(cx)
+        }
+    }
+
+    /// Forwards actions received through a [`TaskDispatcher`] into `sink`.
+    fn spawn<A, T>(
+        sink: T,
+    ) -> (
+        impl Future<Output = Result<(), T::Error>>,
+        TaskDispatcher<A>,
+    )
+    where
+        T: Sink<A>,
+    {
+        let (dispatcher, actions) = TaskDispatcher::new();
+        let future = actions.map(Ok).forward(sink);
+
+        (future, dispatcher)
+    }
 }
 
 #[cfg(feature = "async")]
@@ -282,7 +836,16 @@ mod tests {
     use crate::reactor::AsyncReactor;
 
     #[cfg(feature = "async")]
-    use futures::SinkExt;
+    use crate::reducer::{Effect, MockEffectReducer};
+
+    #[cfg(feature = "async")]
+    use mockall::Sequence;
+
+    #[cfg(feature = "async")]
+    use futures::{SinkExt, StreamExt};
+
+    #[cfg(feature = "async")]
+    use std::vec::Vec;
 
     #[cfg(feature = "async")]
     use tokio::runtime;
@@ -369,6 +932,70 @@ mod tests {
         assert_eq!(Dispatcher::dispatch(&mut store, action), result);
     }
 
+    #[cfg(feature = "tracing")]
+    #[proptest]
+    fn dispatch_debug(action: u8, result: Result<(), u8>) {
+        use std::{format, string::String, sync::Arc, sync::Mutex, vec::Vec};
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata, Subscriber};
+
+        #[derive(Default)]
+        struct Fields(Arc<Mutex<Vec<String>>>);
+
+        impl Visit for Fields {
+            fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+                let field = format!("{}={:?}", field, value);
+                self.0.lock().unwrap().push(field);
+            }
+        }
+
+        struct Recorder(Arc<Mutex<Vec<String>>>);
+
+        impl Subscriber for Recorder {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+
+            fn new_span(&self, span: &Attributes<'_>) -> Id {
+                span.record(&mut Fields(self.0.clone()));
+                Id::from_u64(1)
+            }
+
+            fn record(&self, _: &Id, _: &Record<'_>) {}
+
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+            fn event(&self, event: &Event<'_>) {
+                event.record(&mut Fields(self.0.clone()));
+            }
+
+            fn enter(&self, _: &Id) {}
+
+            fn exit(&self, _: &Id) {}
+        }
+
+        let mut reducer = MockReducer::new();
+        reducer.expect_reduce().once().return_const(());
+
+        let mut reactor = MockReactor::new();
+        reactor.expect_react().once().return_const(result);
+
+        let fields = Arc::new(Mutex::new(Vec::new()));
+        let mut store = Store::new(reducer, reactor);
+
+        tracing::subscriber::with_default(Recorder(fields.clone()), || {
+            assert_eq!(store.dispatch_debug(action), result);
+        });
+
+        let fields = fields.lock().unwrap();
+        assert!(fields.contains(&format!("action.debug={:?}", action)));
+
+        if let Err(e) = result {
+            assert!(fields.contains(&format!("error.debug={:?}", e)));
+        }
+    }
+
     #[cfg(feature = "async")]
     #[proptest]
     fn sink(action: u8, result: Result<(), u8>, id: usize) {
@@ -438,6 +1065,261 @@ mod tests {
         assert_eq!(rt.block_on(handle)?, result);
     }
 
+    #[cfg(feature = "async")]
+    #[proptest]
+    fn watch(actions: Vec<u8>, id: usize) {
+        let rt = runtime::Builder::new_multi_thread().build()?;
+        let mut reducer = MockReducer::new();
+        reducer.expect_id().return_const(id);
+        reducer.expect_clone().returning(move || {
+            let mut mock = MockReducer::new();
+            mock.expect_id().return_const(id);
+            mock.expect_reduce().never();
+            mock.expect_clone().never();
+            mock
+        });
+
+        reducer
+            .expect_reduce()
+            .times(actions.len())
+            .return_const(());
+
+        let mut reactor = MockReactor::new();
+        reactor
+            .expect_react()
+            .with(function(move |x: &MockReducer<_>| x.id() == id))
+            .times(actions.len())
+            .return_const(Ok::<(), ()>(()));
+
+        let store = Store::new(reducer, AsyncReactor(reactor));
+        let (task, mut dispatcher, mut watcher) = store.into_watched_task();
+
+        let handle = rt.spawn(task);
+
+        assert_eq!(rt.block_on(watcher.next()).map(|s| s.id()), Some(id));
+
+        for &action in &actions {
+            assert_eq!(rt.block_on(dispatcher.send(action)), Ok(()));
+        }
+
+        assert_eq!(rt.block_on(dispatcher.close()), Ok(()));
+        assert_eq!(rt.block_on(handle)?, Ok(()));
+
+        assert_eq!(watcher.latest().id(), id);
+
+        if !actions.is_empty() {
+            assert_eq!(rt.block_on(watcher.next()).map(|s| s.id()), Some(id));
+        }
+
+        assert!(rt.block_on(watcher.next()).is_none());
+    }
+
+    #[cfg(feature = "async")]
+    #[proptest]
+    fn effect(action: u8, result: u8, id: usize) {
+        let rt = runtime::Builder::new_multi_thread().build()?;
+        let mut seq = Sequence::new();
+        let mut reducer = MockEffectReducer::new();
+        reducer.expect_id().return_const(id);
+        reducer.expect_clone().returning(move || {
+            let mut mock = MockEffectReducer::new();
+            mock.expect_id().return_const(id);
+            mock.expect_reduce().never();
+            mock.expect_clone().never();
+            mock
+        });
+
+        reducer
+            .expect_reduce()
+            .with(eq(action))
+            .once()
+            .in_sequence(&mut seq)
+            .returning(move |_| Effect::future(async move { result }));
+
+        reducer
+            .expect_reduce()
+            .with(eq(result))
+            .once()
+            .in_sequence(&mut seq)
+            .returning(|_| Effect::None);
+
+        let mut reactor = MockReactor::new();
+        reactor
+            .expect_react()
+            .with(function(move |x: &MockEffectReducer<_>| x.id() == id))
+            .times(2)
+            .return_const(Ok::<(), ()>(()));
+
+        let store = Store::new(reducer, AsyncReactor(reactor));
+        let (task, mut dispatcher) = store.into_effect_task();
+
+        let handle = rt.spawn(task);
+
+        assert_eq!(rt.block_on(dispatcher.send(action)), Ok(()));
+        assert_eq!(rt.block_on(dispatcher.close()), Ok(()));
+        assert_eq!(rt.block_on(handle)?, Ok(()));
+    }
+
+    #[cfg(feature = "async")]
+    #[proptest]
+    fn hydrate(state: Vec<u8>, hydrated: Vec<u8>, before: Vec<u8>, after: Vec<u8>, replay: bool) {
+        #[derive(Debug, Clone, Eq, PartialEq)]
+        struct History(Vec<u8>);
+
+        impl Reducer<u8> for History {
+            fn reduce(&mut self, action: u8) {
+                self.0.push(action);
+            }
+        }
+
+        let rt = runtime::Builder::new_multi_thread().build()?;
+        let (tx, rx) = futures::channel::mpsc::unbounded();
+        let (loaded, load) = futures::channel::oneshot::channel();
+
+        let hydration = if replay {
+            Hydration::Replay
+        } else {
+            Hydration::Queue
+        };
+
+        let store = Store::new(History(state.clone()), AsyncReactor(tx));
+        let (task, mut dispatcher) =
+            store.into_hydrated_task(async { load.await.unwrap() }, hydration);
+
+        let handle = rt.spawn(task);
+
+        for &action in &before {
+            assert_eq!(rt.block_on(dispatcher.send(action)), Ok(()));
+        }
+
+        // Wait for the task to receive all actions.
+        while dispatcher.pending() > 0 {
+            yield_now();
+        }
+
+        assert_eq!(loaded.send(History(hydrated.clone())), Ok(()));
+
+        for &action in &after {
+            assert_eq!(rt.block_on(dispatcher.send(action)), Ok(()));
+        }
+
+        assert_eq!(rt.block_on(dispatcher.close()), Ok(()));
+        assert_eq!(rt.block_on(handle)?, Ok(()));
+
+        let mut expected = Vec::new();
+
+        if replay {
+            for i in 1..=before.len() {
+                expected.push(History([&state[..], &before[..i]].concat()));
+            }
+        }
+
+        for i in 0..=after.len() {
+            expected.push(History([&hydrated[..], &before, &after[..i]].concat()));
+        }
+
+        assert_eq!(rt.block_on(rx.collect::<Vec<_>>()), expected);
+    }
+
+    #[cfg(feature = "async")]
+    #[proptest]
+    fn coalesce(state: u8, actions: Vec<u8>) {
+        #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+        struct Sum(u64);
+
+        impl Reducer<u64> for Sum {
+            fn reduce(&mut self, action: u64) {
+                self.0 += action;
+            }
+        }
+
+        let rt = runtime::Builder::new_multi_thread().build()?;
+        let (tx, rx) = futures::channel::mpsc::unbounded();
+
+        let store = Store::new(Sum(state.into()), AsyncReactor(tx));
+        let (task, mut dispatcher) = store.into_coalesced_task(|a: u64, b: u64| {
+            if a % 2 == b % 2 {
+                Ok(a + b)
+            } else {
+                Err((a, b))
+            }
+        });
+
+        let handle = rt.spawn(task);
+
+        for &action in &actions {
+            assert_eq!(rt.block_on(dispatcher.send(action.into())), Ok(()));
+        }
+
+        assert_eq!(rt.block_on(dispatcher.close()), Ok(()));
+        assert_eq!(rt.block_on(handle)?, Ok(()));
+
+        let mut sums = actions.iter().scan(u64::from(state), |sum, &action| {
+            *sum += u64::from(action);
+            Some(Sum(*sum))
+        });
+
+        let states = rt.block_on(rx.collect::<Vec<_>>());
+
+        // Every notification corresponds to some prefix of the actions.
+        for s in &states {
+            assert!(sums.any(|sum| sum == *s));
+        }
+
+        let total = actions.iter().map(|&a| u64::from(a)).sum::<u64>() + u64::from(state);
+        assert_eq!(
+            states.last().copied(),
+            (!actions.is_empty()).then_some(Sum(total))
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[proptest]
+    fn busy(first: u8, actions: Vec<u8>) {
+        #[derive(Debug, Default, Clone, Eq, PartialEq)]
+        struct History(Vec<u8>);
+
+        impl Reducer<Vec<u8>> for History {
+            fn reduce(&mut self, actions: Vec<u8>) {
+                self.0.extend(actions);
+            }
+        }
+
+        let rt = runtime::Builder::new_current_thread().build()?;
+
+        // The reactor stays busy until the state it was notified of is received.
+        let (tx, rx) = futures::channel::mpsc::channel(0);
+        let (task, mut dispatcher) = Store::new(History::default(), AsyncReactor(tx))
+            .into_coalesced_task(|mut a: Vec<u8>, b: Vec<u8>| {
+                a.extend(b);
+                Ok(a)
+            });
+
+        let handle = rt.spawn(task);
+
+        // Sending does not wait for the reactor.
+        rt.block_on(async {
+            dispatcher.send(vec![first]).await?;
+
+            for &action in &actions {
+                dispatcher.send(vec![action]).await?;
+            }
+
+            dispatcher.close().await
+        })?;
+
+        let states: Vec<History> = rt.block_on(rx.collect());
+        assert_eq!(rt.block_on(handle)?, Ok(()));
+
+        // All actions sent while the reactor was busy are merged into a single one.
+        let mut expected = vec![History(vec![first])];
+        if !actions.is_empty() {
+            expected.push(History([&[first], &actions[..]].concat()));
+        }
+
+        assert_eq!(states, expected);
+    }
+
     #[cfg(feature = "async")]
     #[proptest]
     fn error(action: u8, error: u8, id: usize) {
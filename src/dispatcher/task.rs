@@ -0,0 +1,388 @@
+use crate::dispatcher::*;
+use futures::channel::mpsc::{channel, Receiver, Sender};
+use futures::sink::{Sink, SinkExt};
+use futures::stream::{select_with_strategy, PollNext, SelectWithStrategy, Stream};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// The priority lane through which a [`TaskDispatcher`] sends actions (requires [`async`]).
+///
+/// [`async`]: index.html#optional-features
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Priority {
+    /// Actions are processed before any pending low priority action.
+    #[default]
+    High,
+
+    /// Actions are only processed once there are no pending high priority actions.
+    Low,
+}
+
+/// The asynchronous [`Dispatcher`] handle to a [spawned] [`Store`] (requires [`async`]).
+///
+/// Every [`TaskDispatcher`] sends actions through one of two [priority lanes][`Priority`].
+/// The spawned task always drains pending [high] priority actions first, which makes it
+/// possible to keep the application responsive to user input, while actions of lesser
+/// importance, such as background synchronization, are dispatched at [low] priority.
+///
+/// By default, actions are dispatched at [high] priority.
+///
+/// [spawned]: Store::into_task
+/// [high]: TaskDispatcher::high
+/// [low]: TaskDispatcher::low
+/// [`async`]: index.html#optional-features
+///
+/// # Example
+///
+/// ```rust
+/// use reducer::*;
+/// use futures::prelude::*;
+/// use std::error::Error;
+/// use std::sync::{Arc, Mutex};
+/// use tokio::task::spawn;
+///
+/// #[derive(Debug, Clone)]
+/// enum Action {
+///     Sync,
+///     Click,
+/// }
+///
+/// #[derive(Default, Clone)]
+/// struct Log(Arc<Mutex<Vec<String>>>);
+///
+/// impl Reducer<Action> for Log {
+///     fn reduce(&mut self, action: Action) {
+///         self.0.lock().unwrap().push(format!("{:?}", action));
+///     }
+/// }
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn Error>> {
+///     let log = Log::default();
+///     let store = Store::new(log.clone(), AsyncReactor(sink::drain()));
+///     let (task, dispatcher) = store.into_task();
+///
+///     let mut background = dispatcher.low();
+///     let mut interactive = dispatcher.high();
+///
+///     // Queue both actions before the task starts.
+///     background.feed(Action::Sync).await?;
+///     interactive.feed(Action::Click).await?;
+///     drop(dispatcher);
+///
+///     background.close().await?;
+///     interactive.close().await?;
+///     spawn(task).await??;
+///
+///     // The high priority action was processed first.
+///     assert_eq!(*log.0.lock().unwrap(), ["Click", "Sync"]);
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct TaskDispatcher<A> {
+    high: Sender<A>,
+    low: Sender<A>,
+    priority: Priority,
+    pending: Arc<AtomicUsize>,
+}
+
+impl<A> TaskDispatcher<A> {
+    /// Creates a [`TaskDispatcher`] and the stream of actions it dispatches,
+    /// high priority actions first.
+    pub(crate) fn new() -> (Self, impl Stream<Item = A>) {
+        let (high, high_rx): (_, Receiver<A>) = channel(0);
+        let (low, low_rx): (_, Receiver<A>) = channel(0);
+        let pending = Arc::new(AtomicUsize::new(0));
+
+        let actions = Received {
+            actions: select_with_strategy(high_rx, low_rx, |_| PollNext::Left),
+            pending: pending.clone(),
+        };
+
+        let dispatcher = TaskDispatcher {
+            high,
+            low,
+            priority: Priority::High,
+            pending,
+        };
+
+        (dispatcher, actions)
+    }
+
+    /// The [`Priority`] at which actions are dispatched.
+    pub fn priority(&self) -> Priority {
+        self.priority
+    }
+
+    /// The number of actions sent through any [`TaskDispatcher`] that the spawned task
+    /// has yet to receive.
+    pub fn pending(&self) -> usize {
+        self.pending.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn pending_counter(&self) -> Arc<AtomicUsize> {
+        self.pending.clone()
+    }
+
+    /// Returns a new [`TaskDispatcher`] that dispatches at the given [`Priority`].
+    pub fn with_priority(&self, priority: Priority) -> Self {
+        TaskDispatcher {
+            priority,
+            ..self.clone()
+        }
+    }
+
+    /// Returns a new [`TaskDispatcher`] that dispatches at [high](Priority::High) priority.
+    pub fn high(&self) -> Self {
+        self.with_priority(Priority::High)
+    }
+
+    /// Returns a new [`TaskDispatcher`] that dispatches at [low](Priority::Low) priority.
+    pub fn low(&self) -> Self {
+        self.with_priority(Priority::Low)
+    }
+
+    fn lane(&mut self) -> Pin<&mut Sender<A>> {
+        match self.priority {
+            Priority::High => Pin::new(&mut self.high),
+            Priority::Low => Pin::new(&mut self.low),
+        }
+    }
+}
+
+type Lanes<A> = SelectWithStrategy<Receiver<A>, Receiver<A>, fn(&mut ()) -> PollNext, ()>;
+
+/// The stream of actions sent through [`TaskDispatcher`]s, high priority actions first.
+struct Received<A> {
+    actions: Lanes<A>,
+    pending: Arc<AtomicUsize>,
+}
+
+impl<A> Stream for Received<A> {
+    type Item = A;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<A>> {
+        let action = Pin::new(&mut self.actions).poll_next(cx);
+
+        if let Poll::Ready(Some(_)) = action {
+            self.pending.fetch_sub(1, Ordering::Relaxed);
+        }
+
+        action
+    }
+}
+
+impl<A> Drop for Received<A> {
+    /// Discounts actions that were sent, but will never be received.
+    fn drop(&mut self) {
+        let (high, low) = self.actions.get_mut();
+        high.close();
+        low.close();
+
+        while high.try_recv().is_ok() || low.try_recv().is_ok() {
+            self.pending.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+}
+
+impl<A> Clone for TaskDispatcher<A> {
+    fn clone(&self) -> Self {
+        TaskDispatcher {
+            high: self.high.clone(),
+            low: self.low.clone(),
+            priority: self.priority,
+            pending: self.pending.clone(),
+        }
+    }
+}
+
+impl<A> Dispatcher<A> for TaskDispatcher<A> {
+    /// Either confirmation that action has been dispatched or the reason why not.
+    type Output = Result<(), DispatchError>;
+
+    /// Sends an action to the spawned task.
+    ///
+    /// Once this call returns, the action may or may not have taken effect,
+    /// but it's guaranteed to eventually do,
+    /// unless the task terminates in between.
+    fn dispatch(&mut self, action: A) -> Self::Output {
+        futures::executor::block_on(self.send(action))
+    }
+}
+
+impl<A> Sink<A> for TaskDispatcher<A> {
+    type Error = DispatchError;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.lane()
+            .poll_ready(cx)
+            .map_err(|_| DispatchError::Terminated)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, action: A) -> Result<(), Self::Error> {
+        // Counted ahead of sending, so the task never observes an underflow.
+        self.pending.fetch_add(1, Ordering::Relaxed);
+
+        let result = self.lane().start_send(action);
+
+        if result.is_err() {
+            self.pending.fetch_sub(1, Ordering::Relaxed);
+        }
+
+        result.map_err(|_| DispatchError::Terminated)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.lane()
+            .poll_flush(cx)
+            .map_err(|_| DispatchError::Terminated)
+    }
+
+    /// Disconnects from both priority lanes.
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let high = Pin::new(&mut self.high).poll_close(cx);
+        let low = Pin::new(&mut self.low).poll_close(cx);
+
+        match (high, low) {
+            (Poll::Ready(Ok(())), Poll::Ready(Ok(()))) => Poll::Ready(Ok(())),
+            (Poll::Ready(Err(_)), _) | (_, Poll::Ready(Err(_))) => {
+                Poll::Ready(Err(DispatchError::Terminated))
+            }
+            _ => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use std::vec::Vec;
+    use test_strategy::proptest;
+    use tokio::runtime;
+
+    #[proptest]
+    fn priority() {
+        let (dispatcher, _) = TaskDispatcher::<()>::new();
+        assert_eq!(dispatcher.priority(), Priority::High);
+        assert_eq!(dispatcher.low().priority(), Priority::Low);
+        assert_eq!(dispatcher.low().high().priority(), Priority::High);
+        assert_eq!(dispatcher.clone().priority(), Priority::High);
+        assert_eq!(dispatcher.low().clone().priority(), Priority::Low);
+    }
+
+    #[proptest]
+    fn lanes(high: Vec<u8>, low: Vec<u8>) {
+        let rt = runtime::Builder::new_multi_thread().build()?;
+        let (dispatcher, actions) = TaskDispatcher::new();
+
+        let mut senders: Vec<_> = low
+            .iter()
+            .map(|&a| (dispatcher.low(), a))
+            .chain(high.iter().map(|&a| (dispatcher.high(), a)))
+            .collect();
+
+        drop(dispatcher);
+
+        for (sender, action) in &mut senders {
+            assert_eq!(rt.block_on(sender.feed(*action)), Ok(()));
+        }
+
+        drop(senders);
+
+        let expected: Vec<_> = high.iter().chain(&low).copied().collect();
+        assert_eq!(rt.block_on(actions.collect::<Vec<_>>()), expected);
+    }
+
+    #[proptest]
+    fn pending(actions: Vec<u8>) {
+        let rt = runtime::Builder::new_multi_thread().build()?;
+        let (dispatcher, mut received) = TaskDispatcher::new();
+
+        let mut senders: Vec<_> = actions.iter().map(|_| dispatcher.low()).collect();
+
+        for (i, (sender, &action)) in senders.iter_mut().zip(&actions).enumerate() {
+            assert_eq!(dispatcher.pending(), i);
+            assert_eq!(rt.block_on(sender.feed(action)), Ok(()));
+        }
+
+        for (i, &action) in actions.iter().enumerate() {
+            assert_eq!(dispatcher.pending(), actions.len() - i);
+            assert_eq!(rt.block_on(received.next()), Some(action));
+        }
+
+        assert_eq!(dispatcher.pending(), 0);
+    }
+
+    #[proptest]
+    fn dropped(actions: Vec<u8>, #[strategy(0..=#actions.len())] received: usize) {
+        let rt = runtime::Builder::new_multi_thread().build()?;
+        let (mut dispatcher, mut stream) = TaskDispatcher::new();
+
+        let mut senders: Vec<_> = actions.iter().map(|_| dispatcher.low()).collect();
+
+        for (sender, &action) in senders.iter_mut().zip(&actions) {
+            assert_eq!(rt.block_on(sender.feed(action)), Ok(()));
+        }
+
+        for &action in &actions[..received] {
+            assert_eq!(rt.block_on(stream.next()), Some(action));
+        }
+
+        drop(stream);
+        assert_eq!(dispatcher.pending(), 0);
+
+        assert_eq!(
+            rt.block_on(dispatcher.send(0)),
+            Err(DispatchError::Terminated)
+        );
+
+        assert_eq!(dispatcher.pending(), 0);
+    }
+
+    #[proptest]
+    fn dispatch(action: u8) {
+        let rt = runtime::Builder::new_multi_thread().build()?;
+        let (mut dispatcher, actions) = TaskDispatcher::new();
+        let handle = rt.spawn(actions.collect::<Vec<_>>());
+
+        assert_eq!(Dispatcher::dispatch(&mut dispatcher, action), Ok(()));
+        assert_eq!(Dispatcher::dispatch(&mut dispatcher.low(), action), Ok(()));
+
+        drop(dispatcher);
+        assert_eq!(rt.block_on(handle)?, [action, action]);
+    }
+
+    #[proptest]
+    fn close(action: u8) {
+        let rt = runtime::Builder::new_multi_thread().build()?;
+        let (mut dispatcher, actions) = TaskDispatcher::new();
+        let mut other = dispatcher.low();
+
+        assert_eq!(rt.block_on(other.feed(action)), Ok(()));
+        assert_eq!(rt.block_on(dispatcher.close()), Ok(()));
+        assert_eq!(rt.block_on(other.close()), Ok(()));
+        assert_eq!(rt.block_on(actions.collect::<Vec<_>>()), [action]);
+    }
+
+    #[proptest]
+    fn terminated(action: u8) {
+        let rt = runtime::Builder::new_multi_thread().build()?;
+        let (mut dispatcher, actions) = TaskDispatcher::new();
+        drop(actions);
+
+        assert_eq!(
+            rt.block_on(dispatcher.send(action)),
+            Err(DispatchError::Terminated)
+        );
+
+        assert_eq!(
+            rt.block_on(dispatcher.low().send(action)),
+            Err(DispatchError::Terminated)
+        );
+    }
+}
@@ -0,0 +1,424 @@
+use crate::dispatcher::{Dispatcher, Store};
+use crate::reactor::Reactor;
+use crate::reducer::Reducer;
+use alloc::{boxed::Box, vec::Vec};
+use core::fmt::{self, Debug, Formatter};
+use core::ops::Deref;
+
+/// The default number of actions between checkpoints.
+const INTERVAL: usize = 32;
+
+struct Entry<S> {
+    replay: Box<dyn Fn(&mut S)>,
+    skipped: bool,
+}
+
+/// A reactive state container that can travel back and forth in time (requires [`alloc`]).
+///
+/// [`TimeTravelStore`] records every action dispatched on it, so the state at any point
+/// in history can be recomputed by replaying actions through [`Reducer::reduce`]
+/// from the closest checkpoint, a copy of the state taken periodically.
+/// Unlike a plain undo stack, individual past actions may be [skipped],
+/// in which case the state is recomputed as if they had never been dispatched.
+///
+/// The [`Reactor`] is notified whenever the state is recomputed.
+/// Dispatching an action after traveling back in time discards all actions ahead of it.
+///
+/// Replaying actions is only meaningful if the [`Reducer`] is deterministic.
+///
+/// [`alloc`]: index.html#optional-features
+/// [skipped]: TimeTravelStore::toggle
+///
+/// # Example
+///
+/// ```rust
+/// use reducer::*;
+/// use std::convert::Infallible;
+///
+/// #[derive(Clone, Default)]
+/// struct Editor(String);
+///
+/// #[derive(Clone)]
+/// struct Type(char);
+///
+/// impl Reducer<Type> for Editor {
+///     fn reduce(&mut self, Type(c): Type) {
+///         self.0.push(c);
+///     }
+/// }
+///
+/// struct Nop;
+///
+/// impl<S> Reactor<S> for Nop {
+///     type Error = Infallible;
+///     fn react(&mut self, _: &S) -> Result<(), Self::Error> {
+///         Ok(())
+///     }
+/// }
+///
+/// let mut store = TimeTravelStore::new(Editor::default(), Nop);
+///
+/// store.dispatch(Type('a')).unwrap();
+/// store.dispatch(Type('b')).unwrap();
+/// store.dispatch(Type('c')).unwrap();
+/// assert_eq!(store.0, "abc");
+///
+/// store.toggle(1).unwrap(); // skips Type('b')
+/// assert_eq!(store.0, "ac");
+///
+/// store.jump_to(1).unwrap();
+/// assert_eq!(store.0, "a");
+///
+/// store.step_back().unwrap();
+/// assert_eq!(store.0, "");
+///
+/// store.jump_to(3).unwrap();
+/// assert_eq!(store.0, "ac");
+/// ```
+pub struct TimeTravelStore<S, R> {
+    store: Store<S, R>,
+    history: Vec<Entry<S>>,
+    checkpoints: Vec<S>,
+    interval: usize,
+    cursor: usize,
+}
+
+impl<S: Debug, R: Debug> Debug for TimeTravelStore<S, R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TimeTravelStore")
+            .field("store", &self.store)
+            .field("len", &self.history.len())
+            .field("cursor", &self.cursor)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S: Clone + Default, R: Default> Default for TimeTravelStore<S, R> {
+    fn default() -> Self {
+        Self::new(S::default(), R::default())
+    }
+}
+
+impl<S: Clone, R> From<Store<S, R>> for TimeTravelStore<S, R> {
+    fn from(store: Store<S, R>) -> Self {
+        TimeTravelStore {
+            checkpoints: alloc::vec![S::clone(&store)],
+            store,
+            history: Vec::new(),
+            interval: INTERVAL,
+            cursor: 0,
+        }
+    }
+}
+
+impl<S, R> Deref for TimeTravelStore<S, R> {
+    type Target = S;
+
+    fn deref(&self) -> &S {
+        &self.store
+    }
+}
+
+impl<S: Clone, R> TimeTravelStore<S, R> {
+    /// Constructs the TimeTravelStore given the initial state and a [`Reactor`].
+    pub fn new(state: S, reactor: R) -> Self {
+        Store::new(state, reactor).into()
+    }
+
+    /// Sets the number of actions between checkpoints, trading memory for replay time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval` is zero.
+    pub fn with_interval(mut self, interval: usize) -> Self {
+        assert_ne!(
+            interval, 0,
+            "the interval between checkpoints must not be zero"
+        );
+        self.checkpoints.truncate(1);
+        self.interval = interval;
+        self
+    }
+
+    /// Replaces the [`Reactor`] and returns the previous one.
+    pub fn subscribe(&mut self, reactor: impl Into<R>) -> R {
+        self.store.subscribe(reactor)
+    }
+
+    /// The number of actions recorded.
+    pub fn len(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Whether no actions have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.history.is_empty()
+    }
+
+    /// The number of recorded actions the current state accounts for.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Whether the action at `index` is skipped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn is_skipped(&self, index: usize) -> bool {
+        self.history[index].skipped
+    }
+
+    /// Recomputes the state as of the first `index` recorded actions and notifies the
+    /// [`Reactor`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the number of recorded actions.
+    pub fn jump_to(&mut self, index: usize) -> Result<(), R::Error>
+    where
+        R: Reactor<S>,
+    {
+        assert!(index <= self.history.len(), "index out of bounds");
+
+        let checkpoint = (index / self.interval).min(self.checkpoints.len() - 1);
+        let (state, reactor) = self.store.parts_mut();
+        *state = self.checkpoints[checkpoint].clone();
+        self.cursor = checkpoint * self.interval;
+
+        while self.cursor < index {
+            let entry = &self.history[self.cursor];
+
+            if !entry.skipped {
+                (entry.replay)(state);
+            }
+
+            self.cursor += 1;
+
+            if self.checkpoints.len() * self.interval == self.cursor {
+                self.checkpoints.push(state.clone());
+            }
+        }
+
+        reactor.react(state)
+    }
+
+    /// Undoes the latest action the current state accounts for.
+    ///
+    /// Does nothing if the state accounts for no actions.
+    pub fn step_back(&mut self) -> Result<(), R::Error>
+    where
+        R: Reactor<S>,
+    {
+        match self.cursor.checked_sub(1) {
+            Some(index) => self.jump_to(index),
+            None => Ok(()),
+        }
+    }
+
+    /// Redoes the earliest action the current state does not account for.
+    ///
+    /// Does nothing if the state accounts for all recorded actions.
+    pub fn step_forward(&mut self) -> Result<(), R::Error>
+    where
+        R: Reactor<S>,
+    {
+        if self.cursor < self.history.len() {
+            self.jump_to(self.cursor + 1)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Toggles whether the action at `index` is skipped.
+    ///
+    /// If the current state accounts for the action,
+    /// it is recomputed and the [`Reactor`] is notified.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn toggle(&mut self, index: usize) -> Result<(), R::Error>
+    where
+        R: Reactor<S>,
+    {
+        let entry = &mut self.history[index];
+        entry.skipped = !entry.skipped;
+        self.checkpoints.truncate(index / self.interval + 1);
+
+        if index < self.cursor {
+            self.jump_to(self.cursor)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<A, S, R> Dispatcher<A> for TimeTravelStore<S, R>
+where
+    A: Clone + 'static,
+    S: Reducer<A> + Clone,
+    R: Reactor<S>,
+{
+    type Output = Result<(), R::Error>;
+
+    /// Records the action, discarding all actions the current state does not account for,
+    /// then updates the state via [`Reducer::reduce`] and notifies the [`Reactor`].
+    fn dispatch(&mut self, action: A) -> Self::Output {
+        self.history.truncate(self.cursor);
+        self.checkpoints.truncate(self.cursor / self.interval + 1);
+
+        let replay = action.clone();
+        self.history.push(Entry {
+            replay: Box::new(move |state: &mut S| state.reduce(replay.clone())),
+            skipped: false,
+        });
+
+        let result = self.store.dispatch(action);
+        self.cursor += 1;
+
+        if self.checkpoints.len() * self.interval == self.cursor {
+            self.checkpoints.push(S::clone(&self.store));
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reactor::MockReactor;
+    use core::convert::Infallible;
+    use test_strategy::{proptest, Arbitrary};
+
+    #[derive(Debug, Default, Clone, Eq, PartialEq)]
+    struct History(Vec<u8>);
+
+    impl Reducer<u8> for History {
+        fn reduce(&mut self, action: u8) {
+            self.0.push(action);
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct Recorder(Vec<History>);
+
+    impl Reactor<History> for Recorder {
+        type Error = Infallible;
+
+        fn react(&mut self, state: &History) -> Result<(), Self::Error> {
+            self.0.push(state.clone());
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, Clone, Arbitrary)]
+    enum Op {
+        Dispatch(u8),
+        Jump(usize),
+        Toggle(usize),
+        Back,
+        Forward,
+    }
+
+    #[proptest]
+    fn default() {
+        let store = TimeTravelStore::<History, Recorder>::default();
+        assert!(store.is_empty());
+        assert_eq!(store.cursor(), 0);
+        assert_eq!(*store, History::default());
+    }
+
+    #[proptest]
+    #[should_panic]
+    fn interval() {
+        TimeTravelStore::new(History::default(), Recorder::default()).with_interval(0);
+    }
+
+    #[proptest]
+    fn travel(#[strategy(1usize..8)] interval: usize, state: Vec<u8>, ops: Vec<Op>) {
+        let mut store = TimeTravelStore::new(History(state.clone()), Recorder::default())
+            .with_interval(interval);
+
+        let mut actions: Vec<(u8, bool)> = Vec::new();
+        let mut cursor = 0;
+
+        for op in ops {
+            let notified = store.store.parts_mut().1 .0.len();
+
+            let expected = match op {
+                Op::Dispatch(action) => {
+                    assert_eq!(store.dispatch(action), Ok(()));
+                    actions.truncate(cursor);
+                    actions.push((action, false));
+                    cursor += 1;
+                    true
+                }
+
+                Op::Jump(index) => {
+                    let index = index % (actions.len() + 1);
+                    assert_eq!(store.jump_to(index), Ok(()));
+                    cursor = index;
+                    true
+                }
+
+                Op::Toggle(index) if !actions.is_empty() => {
+                    let index = index % actions.len();
+                    assert_eq!(store.toggle(index), Ok(()));
+                    actions[index].1 = !actions[index].1;
+                    assert_eq!(store.is_skipped(index), actions[index].1);
+                    index < cursor
+                }
+
+                Op::Toggle(_) => false,
+
+                Op::Back => {
+                    assert_eq!(store.step_back(), Ok(()));
+                    let moved = cursor > 0;
+                    cursor = cursor.saturating_sub(1);
+                    moved
+                }
+
+                Op::Forward => {
+                    assert_eq!(store.step_forward(), Ok(()));
+                    let moved = cursor < actions.len();
+                    cursor += usize::from(moved);
+                    moved
+                }
+            };
+
+            let mut history = state.clone();
+            history.extend(actions[..cursor].iter().filter(|a| !a.1).map(|a| a.0));
+
+            assert_eq!(store.len(), actions.len());
+            assert_eq!(store.cursor(), cursor);
+            assert_eq!(*store, History(history.clone()));
+
+            let notifications = &store.store.parts_mut().1 .0;
+            assert_eq!(notifications.len(), notified + usize::from(expected));
+
+            if expected {
+                assert_eq!(notifications.last(), Some(&History(history)));
+            }
+
+            assert!(store.checkpoints.len() <= store.len() / interval + 1);
+        }
+    }
+
+    #[proptest]
+    fn error(action: u8, results: [Result<(), u8>; 4]) {
+        let mut reactor = MockReactor::new();
+
+        for result in results {
+            reactor.expect_react().once().return_const(result);
+        }
+
+        let mut store = TimeTravelStore::new(History::default(), reactor);
+        assert_eq!(store.dispatch(action), results[0]);
+        assert_eq!(store.step_back(), results[1]);
+        assert_eq!(store.step_forward(), results[2]);
+        assert_eq!(store.toggle(0), results[3]);
+        assert_eq!(*store, History::default());
+    }
+}
@@ -0,0 +1,111 @@
+use crate::dispatcher::*;
+
+macro_rules! impl_dispatcher_for_tuple {
+    ( $($args:ident,)+ ) => {
+        /// Dispatches a clone of the action to all [`Dispatcher`]s in the tuple in order,
+        /// collecting their outputs.
+        ///
+        /// <small>Currently implemented for tuples of up to 12 elements.</small>
+        ///
+        /// # Example
+        ///
+        /// ```rust
+        /// use reducer::*;
+        ///
+        /// #[derive(Clone)]
+        /// struct Action { /* ... */ }
+        ///
+        /// struct Document { /* ... */ }
+        /// struct Analytics { /* ... */ }
+        ///
+        /// impl Reducer<Action> for Document {
+        ///     fn reduce(&mut self, action: Action) {
+        ///         // ...
+        ///     }
+        /// }
+        ///
+        /// impl Reducer<Action> for Analytics {
+        ///     fn reduce(&mut self, action: Action) {
+        ///         // ...
+        ///     }
+        /// }
+        ///
+        /// struct Nop;
+        ///
+        /// impl<S> Reactor<S> for Nop {
+        ///     type Error = std::convert::Infallible;
+        ///     fn react(&mut self, _: &S) -> Result<(), Self::Error> {
+        ///         Ok(())
+        ///     }
+        /// }
+        ///
+        /// let document = Store::new(Document { /* ... */ }, Nop);
+        /// let analytics = Store::new(Analytics { /* ... */ }, Nop);
+        ///
+        /// let mut stores = (document, analytics);
+        ///
+        /// // Both `document` and `analytics` receive the action.
+        /// let (a, b) = stores.dispatch(Action { /* ... */ });
+        /// ```
+        impl<Z, $($args,)+> Dispatcher<Z> for ($($args,)+)
+        where
+            Z: Clone,
+            $($args: Dispatcher<Z>,)+
+        {
+            type Output = ($($args::Output,)+);
+
+            fn dispatch(&mut self, action: Z) -> Self::Output {
+                #[allow(non_snake_case)]
+                let ($($args,)+) = self;
+                ($($args.dispatch(action.clone()),)+)
+            }
+        }
+    };
+}
+
+macro_rules! impl_dispatcher_for_tuples {
+    () => {};
+
+    ( $head:ident $(, $tail:ident)* $(,)? ) => {
+        impl_dispatcher_for_tuples!($($tail,)*);
+        reverse!(impl_dispatcher_for_tuple!($head $(, $tail)*));
+    };
+}
+
+impl_dispatcher_for_tuples!(L, K, J, I, H, G, F, E, D, C, B, A);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockall::{predicate::*, Sequence};
+    use test_strategy::proptest;
+
+    macro_rules! test_dispatcher_for_tuples {
+        () => {};
+
+        ( $head:ident $(, $tail:ident)* $(,)? ) => {
+            #[proptest]
+            fn $head(action: u8, results: [u8; count!($($tail,)*) + 1]) {
+                let mut seq = Sequence::new();
+                let mut mocks: [MockDispatcher<u8, u8>; count!($($tail,)*) + 1] = Default::default();
+
+                for (mock, &result) in mocks.iter_mut().zip(&results) {
+                    mock.expect_dispatch()
+                        .with(eq(action))
+                        .once()
+                        .in_sequence(&mut seq)
+                        .return_const(result);
+                }
+
+                let [$head, $($tail,)*] = mocks;
+                let mut dispatcher = ($head, $($tail,)*);
+                let ($head, $($tail,)*) = Dispatcher::dispatch(&mut dispatcher, action);
+                assert_eq!([$head, $($tail,)*], results);
+            }
+
+            test_dispatcher_for_tuples!($($tail,)*);
+        };
+    }
+
+    test_dispatcher_for_tuples!(_12, _11, _10, _09, _08, _07, _06, _05, _04, _03, _02, _01);
+}
@@ -0,0 +1,76 @@
+use crate::dispatcher::*;
+use alloc::vec::Vec;
+
+/// Dispatches a clone of the action to all [`Dispatcher`]s in the vector in order,
+/// collecting their outputs (requires [`alloc`]).
+///
+/// [`alloc`]: index.html#optional-features
+///
+/// # Example
+///
+/// ```rust
+/// use reducer::*;
+///
+/// #[derive(Clone)]
+/// struct Action { /* ... */ }
+///
+/// struct Channel { /* ... */ }
+///
+/// impl Dispatcher<Action> for Channel {
+///     type Output = bool;
+///     fn dispatch(&mut self, action: Action) -> Self::Output {
+///         // ...
+///         true
+///     }
+/// }
+///
+/// let mut channels = vec![];
+///
+/// channels.push(Channel { /* ... */ });
+/// channels.push(Channel { /* ... */ });
+/// // ...
+/// channels.push(Channel { /* ... */ });
+///
+/// // All channels receive the action.
+/// let outputs: Vec<bool> = channels.dispatch(Action { /* ... */ });
+/// ```
+impl<A, D> Dispatcher<A> for Vec<D>
+where
+    A: Clone,
+    D: Dispatcher<A>,
+{
+    type Output = Vec<D::Output>;
+
+    fn dispatch(&mut self, action: A) -> Self::Output {
+        self.as_mut_slice().dispatch(action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockall::{predicate::*, Sequence};
+    use test_strategy::proptest;
+
+    #[proptest]
+    fn dispatch(action: u8, results: Vec<u8>) {
+        let mut seq = Sequence::new();
+
+        let mut dispatcher: Vec<_> = results
+            .iter()
+            .map(|&result| {
+                let mut mock = MockDispatcher::<u8, u8>::new();
+
+                mock.expect_dispatch()
+                    .with(eq(action))
+                    .once()
+                    .in_sequence(&mut seq)
+                    .return_const(result);
+
+                mock
+            })
+            .collect();
+
+        assert_eq!(Dispatcher::dispatch(&mut dispatcher, action), results);
+    }
+}
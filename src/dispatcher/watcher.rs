@@ -0,0 +1,221 @@
+use futures::stream::{FusedStream, Stream};
+use std::mem::{replace, take};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll, Waker};
+use std::{pin::Pin, vec::Vec};
+
+#[derive(Debug)]
+struct Shared<S> {
+    version: usize,
+    state: Arc<S>,
+    closed: bool,
+    wakers: Vec<Waker>,
+}
+
+fn lock<S>(shared: &Mutex<Shared<S>>) -> MutexGuard<'_, Shared<S>> {
+    // The shared state is always left consistent, so it's safe to recover from poisoning.
+    shared.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// A handle to the latest state of a [spawned] [`Store`](crate::Store) (requires [`async`]).
+///
+/// [`StateWatcher`] is a [`Stream`] with _latest-value_ semantics, that is, it only ever yields
+/// the most recent state and intermediate states are skipped if the consumer lags behind.
+/// The first call to [`poll_next`] yields the current state immediately.
+///
+/// Any number of watchers may be obtained by cloning, each of them keeps track of the
+/// last state it has observed independently.
+/// The stream terminates once the [spawned] task completes.
+///
+/// [spawned]: crate::Store::into_watched_task
+/// [`async`]: index.html#optional-features
+/// [`poll_next`]: Stream::poll_next
+#[derive(Debug)]
+pub struct StateWatcher<S> {
+    shared: Arc<Mutex<Shared<S>>>,
+    version: usize,
+}
+
+impl<S> StateWatcher<S> {
+    /// Returns the latest state without waiting for a change.
+    pub fn latest(&self) -> Arc<S> {
+        lock(&self.shared).state.clone()
+    }
+}
+
+impl<S> Clone for StateWatcher<S> {
+    fn clone(&self) -> Self {
+        StateWatcher {
+            shared: self.shared.clone(),
+            version: self.version,
+        }
+    }
+}
+
+impl<S> Stream for StateWatcher<S> {
+    type Item = Arc<S>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let mut shared = lock(&this.shared);
+
+        if this.version != shared.version {
+            this.version = shared.version;
+            Poll::Ready(Some(shared.state.clone()))
+        } else if shared.closed {
+            Poll::Ready(None)
+        } else {
+            if !shared.wakers.iter().any(|w| w.will_wake(cx.waker())) {
+                shared.wakers.push(cx.waker().clone());
+            }
+
+            Poll::Pending
+        }
+    }
+}
+
+impl<S> FusedStream for StateWatcher<S> {
+    fn is_terminated(&self) -> bool {
+        let shared = lock(&self.shared);
+        shared.closed && self.version == shared.version
+    }
+}
+
+/// The sending half of a [`StateWatcher`], closes all watchers when dropped.
+#[derive(Debug)]
+pub(crate) struct Publisher<S> {
+    shared: Arc<Mutex<Shared<S>>>,
+}
+
+impl<S> Publisher<S> {
+    /// Whether any [`StateWatcher`] is still alive.
+    pub(crate) fn is_watched(&self) -> bool {
+        Arc::strong_count(&self.shared) > 1
+    }
+
+    pub(crate) fn publish(&self, state: Arc<S>) {
+        let mut shared = lock(&self.shared);
+        shared.version = shared.version.wrapping_add(1);
+        let previous = replace(&mut shared.state, state);
+        let wakers = take(&mut shared.wakers);
+        drop(shared);
+
+        drop(previous);
+        wakers.into_iter().for_each(Waker::wake);
+    }
+}
+
+impl<S> Drop for Publisher<S> {
+    fn drop(&mut self) {
+        let mut shared = lock(&self.shared);
+        shared.closed = true;
+        let wakers = take(&mut shared.wakers);
+        drop(shared);
+
+        wakers.into_iter().for_each(Waker::wake);
+    }
+}
+
+/// Creates a [`Publisher`] and a [`StateWatcher`] that observes the initial `state`.
+pub(crate) fn watch<S>(state: Arc<S>) -> (Publisher<S>, StateWatcher<S>) {
+    let shared = Arc::new(Mutex::new(Shared {
+        version: 1,
+        state,
+        closed: false,
+        wakers: Vec::new(),
+    }));
+
+    let watcher = StateWatcher {
+        shared: shared.clone(),
+        version: 0,
+    };
+
+    (Publisher { shared }, watcher)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{executor::block_on, FutureExt, StreamExt};
+    use std::{thread, vec::Vec};
+    use test_strategy::proptest;
+
+    #[proptest]
+    fn latest(states: Vec<u8>, initial: u8) {
+        let (publisher, watcher) = watch(Arc::new(initial));
+        assert_eq!(*watcher.latest(), initial);
+
+        for &state in &states {
+            publisher.publish(Arc::new(state));
+            assert_eq!(*watcher.latest(), state);
+        }
+    }
+
+    #[proptest]
+    fn stream(states: Vec<u8>, initial: u8) {
+        let (publisher, mut watcher) = watch(Arc::new(initial));
+        assert_eq!(watcher.next().now_or_never(), Some(Some(Arc::new(initial))));
+        assert_eq!(watcher.next().now_or_never(), None);
+
+        for &state in &states {
+            publisher.publish(Arc::new(state));
+            assert_eq!(watcher.next().now_or_never(), Some(Some(Arc::new(state))));
+        }
+
+        drop(publisher);
+        assert_eq!(watcher.next().now_or_never(), Some(None));
+        assert!(watcher.is_terminated());
+    }
+
+    #[proptest]
+    fn skips(states: Vec<u8>, initial: u8) {
+        let (publisher, mut watcher) = watch(Arc::new(initial));
+
+        for &state in &states {
+            publisher.publish(Arc::new(state));
+        }
+
+        let last = states.last().copied().unwrap_or(initial);
+        assert_eq!(watcher.next().now_or_never(), Some(Some(Arc::new(last))));
+        assert_eq!(watcher.next().now_or_never(), None);
+    }
+
+    #[proptest]
+    fn clone(a: u8, b: u8) {
+        let (publisher, mut watcher) = watch(Arc::new(a));
+        assert_eq!(watcher.next().now_or_never(), Some(Some(Arc::new(a))));
+
+        let mut other = watcher.clone();
+        assert_eq!(other.next().now_or_never(), None);
+
+        publisher.publish(Arc::new(b));
+        assert_eq!(watcher.next().now_or_never(), Some(Some(Arc::new(b))));
+        assert_eq!(other.next().now_or_never(), Some(Some(Arc::new(b))));
+    }
+
+    #[proptest]
+    fn watched(state: u8) {
+        let (publisher, watcher) = watch(Arc::new(state));
+        let other = watcher.clone();
+        assert!(publisher.is_watched());
+
+        drop(watcher);
+        assert!(publisher.is_watched());
+
+        drop(other);
+        assert!(!publisher.is_watched());
+    }
+
+    #[proptest]
+    fn wake(state: u8) {
+        let (publisher, mut watcher) = watch(Arc::new(state));
+        assert_eq!(watcher.next().now_or_never(), Some(Some(Arc::new(state))));
+
+        let handle = thread::spawn(move || block_on(watcher.collect::<Vec<_>>()));
+
+        publisher.publish(Arc::new(state));
+        drop(publisher);
+
+        assert!(handle.join().unwrap().len() <= 1);
+    }
+}
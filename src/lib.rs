@@ -42,6 +42,38 @@
 //!
 //!     Enables integration with [futures-rs](https://crates.io/crates/futures).
 //!
+//! * `tracing` (disabled by default)
+//!
+//!     Enables integration with [tracing](https://crates.io/crates/tracing).
+//!
+//!     Actions dispatched to a [`Store`] are recorded as spans that carry their type name.
+//!
+//!     [`Store::dispatch_debug`] also records the `Debug` output of actions and errors.
+//!
+//! * `devtools` (disabled by default; implies `std`)
+//!
+//!     Enables integration with [Redux DevTools](https://github.com/reduxjs/redux-devtools).
+//!
+//!     Monitors may jump to recorded states and dispatch actions, other commands are ignored.
+//!
+//!     The protocol is only partially implemented and tested against a minimal client.
+//!
+//! * `derive` (disabled by default)
+//!
+//!     Enables deriving [`Diff`] for structs and enums.
+//!
+//! * `replication` (disabled by default; implies `async`)
+//!
+//!     Enables replicating a [`Store`] to [`Follower`]s over asynchronous byte streams.
+//!
+//! * `ipc` (disabled by default; implies `async`)
+//!
+//!     Enables dispatching actions to a [`Store`] in another process over TCP or Unix sockets.
+//!
+//! * `versioning` (disabled by default; implies `std`)
+//!
+//!     Enables persisting [`Versioned`] states and migrating snapshots saved by earlier versions.
+//!
 //! [crate `alloc`]: https://doc.rust-lang.org/alloc/
 //! [crate `std`]: https://doc.rust-lang.org/std/
 
@@ -56,10 +88,31 @@ extern crate std;
 
 mod macros;
 
+#[cfg(feature = "async")]
+mod clock;
+#[cfg(feature = "devtools")]
+mod devtools;
+mod diff;
 mod dispatcher;
+mod mergeable;
+#[cfg(feature = "std")]
+mod metrics;
 mod reactor;
 mod reducer;
+mod trace;
+#[cfg(feature = "versioning")]
+mod versioned;
 
+#[cfg(feature = "async")]
+pub use crate::clock::*;
+#[cfg(feature = "devtools")]
+pub use crate::devtools::*;
+pub use crate::diff::*;
 pub use crate::dispatcher::*;
+pub use crate::mergeable::*;
+#[cfg(feature = "std")]
+pub use crate::metrics::*;
 pub use crate::reactor::*;
 pub use crate::reducer::*;
+#[cfg(feature = "versioning")]
+pub use crate::versioned::*;
@@ -0,0 +1,204 @@
+#[cfg(feature = "alloc")]
+use alloc::collections::{BTreeMap, BTreeSet};
+
+#[cfg(feature = "std")]
+use std::{
+    collections::{HashMap, HashSet},
+    hash::{BuildHasher, Hash},
+};
+
+/// Trait for states that replicas can merge without coordination.
+///
+/// Merging must be commutative, associative and idempotent,
+/// so replicas that eventually see each other's states converge to the same state
+/// regardless of the order and number of times they merge.
+/// States that don't satisfy these laws can still converge by exchanging actions instead,
+/// see [`ConvergentStore`](crate::ConvergentStore), which also joins the [baselines] of
+/// replicas whose states are [`Mergeable`].
+///
+/// Implementations are provided for [`bool`] (logical _or_), unsigned integers (maximum),
+/// [`Option`], and, if [`alloc`] is enabled, [`BTreeSet`] (union) and [`BTreeMap`]
+/// (union, merging values under the same key),
+/// as well as [`HashSet`](std::collections::HashSet) and
+/// [`HashMap`](std::collections::HashMap) if [`std`] is enabled.
+///
+/// [`alloc`]: index.html#optional-features
+/// [`std`]: index.html#optional-features
+/// [baselines]: crate::Baseline
+///
+/// # Example
+///
+#[cfg_attr(feature = "alloc", doc = "```rust")]
+#[cfg_attr(not(feature = "alloc"), doc = "```ignore")]
+/// use reducer::*;
+/// use std::collections::BTreeMap;
+///
+/// // A last-writer-wins register for every key.
+/// let mut laptop = BTreeMap::from([("title", (1, "Draft")), ("body", (1, "TODO"))]);
+/// let mut phone = laptop.clone();
+///
+/// laptop.join(BTreeMap::from([("title", (2, "Final"))]));
+/// phone.join(BTreeMap::from([("body", (2, "Done"))]));
+///
+/// laptop.join(phone.clone());
+/// phone.join(laptop.clone());
+///
+/// assert_eq!(laptop, phone);
+/// assert_eq!(laptop["title"], (2, "Final"));
+/// assert_eq!(laptop["body"], (2, "Done"));
+/// ```
+pub trait Mergeable {
+    /// Merges the state of another replica into this one.
+    fn join(&mut self, other: Self);
+}
+
+impl Mergeable for bool {
+    fn join(&mut self, other: Self) {
+        *self |= other;
+    }
+}
+
+macro_rules! impl_mergeable_for_ordered {
+    ( $($type:ty),* $(,)? ) => {
+        $(
+            /// Keeps the greatest value.
+            impl Mergeable for $type {
+                fn join(&mut self, other: Self) {
+                    if other > *self {
+                        *self = other;
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_mergeable_for_ordered!(u8, u16, u32, u64, u128, usize);
+
+/// Keeps the greatest value, which allows pairing a value with a version in
+/// last-writer-wins registers.
+impl<T: Ord, U: Ord> Mergeable for (T, U) {
+    fn join(&mut self, other: Self) {
+        if other > *self {
+            *self = other;
+        }
+    }
+}
+
+/// `None` is superseded by any value.
+impl<T: Mergeable> Mergeable for Option<T> {
+    fn join(&mut self, other: Self) {
+        match (self, other) {
+            (Some(a), Some(b)) => a.join(b),
+            (this @ None, other) => *this = other,
+            (Some(_), None) => {}
+        }
+    }
+}
+
+/// Computes the union of the sets (requires [`alloc`]).
+///
+/// [`alloc`]: index.html#optional-features
+#[cfg(feature = "alloc")]
+impl<T: Ord> Mergeable for BTreeSet<T> {
+    fn join(&mut self, other: Self) {
+        self.extend(other);
+    }
+}
+
+/// Computes the union of the maps, merging values with the same key (requires [`alloc`]).
+///
+/// [`alloc`]: index.html#optional-features
+#[cfg(feature = "alloc")]
+impl<K: Ord, V: Mergeable> Mergeable for BTreeMap<K, V> {
+    fn join(&mut self, other: Self) {
+        for (k, v) in other {
+            match self.get_mut(&k) {
+                Some(value) => value.join(v),
+                None => drop(self.insert(k, v)),
+            }
+        }
+    }
+}
+
+/// Computes the union of the sets (requires [`std`]).
+///
+/// [`std`]: index.html#optional-features
+#[cfg(feature = "std")]
+impl<T: Eq + Hash, S: BuildHasher> Mergeable for HashSet<T, S> {
+    fn join(&mut self, other: Self) {
+        self.extend(other);
+    }
+}
+
+/// Computes the union of the maps, merging values with the same key (requires [`std`]).
+///
+/// [`std`]: index.html#optional-features
+#[cfg(feature = "std")]
+impl<K: Eq + Hash, V: Mergeable, S: BuildHasher> Mergeable for HashMap<K, V, S> {
+    fn join(&mut self, other: Self) {
+        for (k, v) in other {
+            match self.get_mut(&k) {
+                Some(value) => value.join(v),
+                None => drop(self.insert(k, v)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::fmt::Debug;
+    use proptest::prelude::*;
+    use test_strategy::proptest;
+
+    fn merged<T: Mergeable>(mut a: T, b: T) -> T {
+        a.join(b);
+        a
+    }
+
+    fn laws<T>(a: T, b: T, c: T) -> Result<(), TestCaseError>
+    where
+        T: Mergeable + Debug + Clone + PartialEq,
+    {
+        prop_assert_eq!(merged(a.clone(), b.clone()), merged(b.clone(), a.clone()));
+        prop_assert_eq!(merged(a.clone(), a.clone()), a.clone());
+        prop_assert_eq!(
+            merged(merged(a.clone(), b.clone()), c.clone()),
+            merged(a, merged(b, c))
+        );
+
+        Ok(())
+    }
+
+    #[proptest]
+    fn values(a: (bool, u8, Option<u32>), b: (bool, u8, Option<u32>), c: (bool, u8, Option<u32>)) {
+        laws(a.0, b.0, c.0)?;
+        laws(a.1, b.1, c.1)?;
+        laws(a.2, b.2, c.2)?;
+        laws((a.1, a.0), (b.1, b.0), (c.1, c.0))?;
+    }
+
+    #[cfg(feature = "alloc")]
+    #[proptest]
+    fn btree(
+        a: (BTreeMap<u8, u8>, BTreeSet<u8>),
+        b: (BTreeMap<u8, u8>, BTreeSet<u8>),
+        c: (BTreeMap<u8, u8>, BTreeSet<u8>),
+    ) {
+        laws(a.0, b.0, c.0)?;
+        laws(a.1, b.1, c.1)?;
+    }
+
+    #[cfg(feature = "std")]
+    #[proptest]
+    fn hash(
+        a: (HashMap<u8, u8>, HashSet<u8>),
+        b: (HashMap<u8, u8>, HashSet<u8>),
+        c: (HashMap<u8, u8>, HashSet<u8>),
+    ) {
+        laws(a.0, b.0, c.0)?;
+        laws(a.1, b.1, c.1)?;
+    }
+}
@@ -0,0 +1,555 @@
+use crate::reactor::Reactor;
+use crate::reducer::Reducer;
+use core::any::type_name;
+use core::time::Duration;
+use derive_more::Deref;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::string::String;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Instant;
+use std::vec::Vec;
+
+#[cfg(feature = "async")]
+use crate::dispatcher::TaskDispatcher;
+
+#[cfg(feature = "async")]
+use pin_project::pin_project;
+
+/// Aggregate timings of an operation (requires [`std`]).
+///
+/// [`std`]: index.html#optional-features
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Timing {
+    /// How many times the operation was performed.
+    pub count: u64,
+
+    /// The total time spent performing the operation.
+    pub total: Duration,
+
+    /// The longest time the operation took.
+    pub max: Duration,
+}
+
+impl Timing {
+    fn record(&mut self, elapsed: Duration) {
+        self.count += 1;
+        self.total += elapsed;
+        self.max = self.max.max(elapsed);
+    }
+}
+
+/// Metrics of a type of action (requires [`std`]).
+///
+/// [`std`]: index.html#optional-features
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct ActionMetrics {
+    /// The type name of the action.
+    pub action: &'static str,
+
+    /// Time spent reducing actions of this type.
+    pub reduce: Timing,
+
+    /// Time spent notifying the [`Reactor`] of transitions caused by actions of this type.
+    pub react: Timing,
+
+    /// How many times the [`Reactor`] failed.
+    pub errors: u64,
+}
+
+/// A point-in-time copy of [`Metrics`] (requires [`std`]).
+///
+/// [`std`]: index.html#optional-features
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct Snapshot {
+    /// Time elapsed since [`Metrics`] started recording, which is useful to derive throughput.
+    pub elapsed: Duration,
+
+    /// Metrics of every type of action dispatched so far, ordered by type name.
+    pub actions: Vec<ActionMetrics>,
+
+    /// The number of actions pending on the [tracked] [`TaskDispatcher`](crate::TaskDispatcher),
+    /// if any.
+    ///
+    /// [tracked]: Metrics::track
+    pub queue_depth: Option<usize>,
+}
+
+impl Snapshot {
+    /// Writes the figures in the [Prometheus text format].
+    ///
+    /// [Prometheus text format]: https://prometheus.io/docs/instrumenting/exposition_formats/
+    pub fn write_prometheus<W: Write>(&self, mut w: W) -> io::Result<()> {
+        type Field = fn(&ActionMetrics) -> &Timing;
+
+        let timings: [(&str, &str, Field); 2] = [
+            ("reduce", "Time spent reducing actions.", |m| &m.reduce),
+            ("react", "Time spent notifying the reactor.", |m| &m.react),
+        ];
+
+        writeln!(
+            w,
+            "# HELP reducer_actions_total Number of dispatched actions."
+        )?;
+        writeln!(w, "# TYPE reducer_actions_total counter")?;
+        for m in &self.actions {
+            let label = escape(m.action);
+            writeln!(
+                w,
+                "reducer_actions_total{{action=\"{}\"}} {}",
+                label, m.reduce.count
+            )?;
+        }
+
+        for (name, help, timing) in timings {
+            writeln!(w, "# HELP reducer_{}_seconds {}", name, help)?;
+            writeln!(w, "# TYPE reducer_{}_seconds summary", name)?;
+            for m in &self.actions {
+                let (label, t) = (escape(m.action), timing(m));
+                let (sum, count) = (t.total.as_secs_f64(), t.count);
+                writeln!(
+                    w,
+                    "reducer_{}_seconds_sum{{action=\"{}\"}} {}",
+                    name, label, sum
+                )?;
+                writeln!(
+                    w,
+                    "reducer_{}_seconds_count{{action=\"{}\"}} {}",
+                    name, label, count
+                )?;
+            }
+
+            writeln!(w, "# HELP reducer_{}_seconds_max {}", name, help)?;
+            writeln!(w, "# TYPE reducer_{}_seconds_max gauge", name)?;
+            for m in &self.actions {
+                let (label, max) = (escape(m.action), timing(m).max.as_secs_f64());
+                writeln!(
+                    w,
+                    "reducer_{}_seconds_max{{action=\"{}\"}} {}",
+                    name, label, max
+                )?;
+            }
+        }
+
+        writeln!(
+            w,
+            "# HELP reducer_react_errors_total Number of reactor failures."
+        )?;
+        writeln!(w, "# TYPE reducer_react_errors_total counter")?;
+        for m in &self.actions {
+            let label = escape(m.action);
+            writeln!(
+                w,
+                "reducer_react_errors_total{{action=\"{}\"}} {}",
+                label, m.errors
+            )?;
+        }
+
+        if let Some(depth) = self.queue_depth {
+            writeln!(w, "# HELP reducer_queue_depth Number of pending actions.")?;
+            writeln!(w, "# TYPE reducer_queue_depth gauge")?;
+            writeln!(w, "reducer_queue_depth {}", depth)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Escapes a Prometheus label value.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('"', r#"\""#)
+        .replace('\n', r"\n")
+}
+
+#[derive(Debug)]
+struct Registry {
+    start: Instant,
+    actions: BTreeMap<&'static str, ActionMetrics>,
+    queue: Option<Arc<AtomicUsize>>,
+}
+
+/// Records how long it takes to dispatch actions (requires [`std`]).
+///
+/// [`Metrics`] is a lightweight handle that can be cloned and sent to other threads,
+/// all clones refer to the same figures.
+/// It records reduce times through [`Metered`] states and react times through
+/// [`Metered`] [`Reactor`]s, broken down by the type of action,
+/// as well as the depth of the queue of a [spawned] [`Store`](crate::Store).
+///
+/// [spawned]: crate::Store::into_task
+/// [`std`]: index.html#optional-features
+///
+/// # Example
+///
+/// ```rust
+/// use reducer::*;
+/// use std::convert::Infallible;
+///
+/// #[derive(Default)]
+/// struct Counter(u32);
+///
+/// struct Increment;
+///
+/// impl Reducer<Increment> for Counter {
+///     fn reduce(&mut self, _: Increment) {
+///         self.0 += 1;
+///     }
+/// }
+///
+/// struct Nop;
+///
+/// impl<S> Reactor<S> for Nop {
+///     type Error = Infallible;
+///     fn react(&mut self, _: &S) -> Result<(), Self::Error> {
+///         Ok(())
+///     }
+/// }
+///
+/// let metrics = Metrics::new();
+/// let mut store = Store::new(metrics.reducer(Counter::default()), metrics.reactor(Nop));
+///
+/// store.dispatch(Increment).unwrap();
+/// store.dispatch(Increment).unwrap();
+///
+/// let snapshot = metrics.snapshot();
+/// assert_eq!(snapshot.actions[0].reduce.count, 2);
+/// assert_eq!(snapshot.actions[0].react.count, 2);
+///
+/// let mut exposition = Vec::new();
+/// snapshot.write_prometheus(&mut exposition).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Metrics(Arc<Mutex<Registry>>);
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Metrics {
+    /// Starts recording.
+    pub fn new() -> Self {
+        Metrics(Arc::new(Mutex::new(Registry {
+            start: Instant::now(),
+            actions: BTreeMap::new(),
+            queue: None,
+        })))
+    }
+
+    fn registry(&self) -> MutexGuard<'_, Registry> {
+        // The registry is always left consistent, so it's safe to recover from poisoning.
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Wraps the state to record reduce times.
+    pub fn reducer<S>(&self, state: S) -> Metered<S> {
+        Metered {
+            inner: state,
+            metrics: self.clone(),
+            action: None,
+        }
+    }
+
+    /// Wraps the [`Reactor`] to record react times.
+    pub fn reactor<R>(&self, reactor: R) -> Metered<R> {
+        self.reducer(reactor)
+    }
+
+    /// Records the depth of the queue of actions sent through the [`TaskDispatcher`]
+    /// (requires [`async`]).
+    ///
+    /// [`async`]: index.html#optional-features
+    #[cfg(feature = "async")]
+    pub fn track<A>(&self, dispatcher: &TaskDispatcher<A>) {
+        self.registry().queue = Some(dispatcher.pending_counter());
+    }
+
+    /// Returns a copy of the figures recorded so far.
+    pub fn snapshot(&self) -> Snapshot {
+        let registry = self.registry();
+
+        Snapshot {
+            elapsed: registry.start.elapsed(),
+            actions: registry.actions.values().cloned().collect(),
+            queue_depth: registry.queue.as_ref().map(|q| q.load(Ordering::Relaxed)),
+        }
+    }
+
+    fn reduced(&self, action: &'static str, elapsed: Duration) {
+        let mut registry = self.registry();
+        let metrics = registry.actions.entry(action).or_insert(ActionMetrics {
+            action,
+            ..ActionMetrics::default()
+        });
+
+        metrics.reduce.record(elapsed);
+    }
+
+    fn reacted(&self, action: Option<&'static str>, elapsed: Duration, failed: bool) {
+        let mut registry = self.registry();
+
+        if let Some(metrics) = action.and_then(|a| registry.actions.get_mut(a)) {
+            metrics.react.record(elapsed);
+            metrics.errors += u64::from(failed);
+        }
+    }
+}
+
+/// A state or [`Reactor`] whose timings are recorded by [`Metrics`] (requires [`std`]).
+///
+/// [`std`]: index.html#optional-features
+#[cfg_attr(feature = "async", pin_project)]
+#[derive(Debug, Clone, Deref)]
+pub struct Metered<T> {
+    #[deref]
+    #[cfg_attr(feature = "async", pin)]
+    inner: T,
+    metrics: Metrics,
+    // The last action reduced by a state, which react times are attributed to,
+    // even if the same Metrics are shared by several stores.
+    action: Option<&'static str>,
+}
+
+impl<T> Metered<T> {
+    /// Returns the wrapped state or [`Reactor`].
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<A, S> Reducer<A> for Metered<S>
+where
+    S: Reducer<A>,
+{
+    fn reduce(&mut self, action: A) {
+        let start = Instant::now();
+        self.inner.reduce(action);
+        self.action = Some(type_name::<A>());
+        self.metrics.reduced(type_name::<A>(), start.elapsed());
+    }
+}
+
+impl<S, R> Reactor<Metered<S>> for Metered<R>
+where
+    R: Reactor<S>,
+{
+    type Error = R::Error;
+
+    fn react(&mut self, state: &Metered<S>) -> Result<(), Self::Error> {
+        let start = Instant::now();
+        let result = self.inner.react(&state.inner);
+        self.metrics
+            .reacted(state.action, start.elapsed(), result.is_err());
+        result
+    }
+}
+
+#[cfg(feature = "async")]
+mod sink {
+    use super::*;
+    use futures::sink::Sink;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    /// View a [`Metered`] [`Sink`] of states as a [`Sink`] of [`Metered`] states
+    /// (requires [`async`]).
+    ///
+    /// React times only account for sending the state, not for flushing it.
+    ///
+    /// [`async`]: index.html#optional-features
+    impl<'s, S, R> Sink<&'s Metered<S>> for Metered<R>
+    where
+        R: Sink<&'s S>,
+    {
+        type Error = R::Error;
+
+        fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            self.project().inner.poll_ready(cx)
+        }
+
+        fn start_send(self: Pin<&mut Self>, state: &'s Metered<S>) -> Result<(), Self::Error> {
+            let this = self.project();
+            let start = Instant::now();
+            let result = this.inner.start_send(&state.inner);
+            this.metrics
+                .reacted(state.action, start.elapsed(), result.is_err());
+            result
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            self.project().inner.poll_flush(cx)
+        }
+
+        fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            self.project().inner.poll_close(cx)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reactor::MockReactor;
+    use crate::reducer::MockReducer;
+    use test_strategy::proptest;
+
+    #[proptest]
+    fn reduce(actions: Vec<u8>, other: Vec<u16>) {
+        let mut mock = MockReducer::<u8>::new();
+        mock.expect_reduce().times(actions.len()).return_const(());
+
+        let metrics = Metrics::default();
+        let mut reducer = metrics.clone().reducer(mock);
+        actions.iter().for_each(|&a| reducer.reduce(a));
+
+        let mut mock = MockReducer::<u16>::new();
+        mock.expect_reduce().times(other.len()).return_const(());
+
+        let mut reducer = metrics.reducer(mock);
+        other.iter().for_each(|&a| reducer.reduce(a));
+
+        let snapshot = metrics.snapshot();
+        let counts: Vec<_> = snapshot
+            .actions
+            .iter()
+            .map(|m| (m.action, m.reduce.count, m.react.count))
+            .collect();
+
+        let mut expected = Vec::new();
+
+        if !other.is_empty() {
+            expected.push(("u16", other.len() as u64, 0));
+        }
+
+        if !actions.is_empty() {
+            expected.push(("u8", actions.len() as u64, 0));
+        }
+
+        assert_eq!(counts, expected);
+        assert_eq!(snapshot.queue_depth, None);
+
+        for m in &snapshot.actions {
+            assert!(m.reduce.max <= m.reduce.total);
+        }
+    }
+
+    #[proptest]
+    fn react(action: u8, results: Vec<Result<(), u8>>) {
+        let mut reducer = MockReducer::<u8>::new();
+        reducer.expect_reduce().return_const(());
+
+        let mut reactor = MockReactor::<MockReducer<u8>, u8>::new();
+        for &result in &results {
+            reactor.expect_react().once().return_const(result);
+        }
+
+        let metrics = Metrics::new();
+        let mut state = metrics.reducer(reducer);
+        let mut reactor = metrics.reactor(reactor);
+
+        // Transitions not caused by a known action are not recorded.
+        if let Some(&result) = results.first() {
+            assert_eq!(reactor.react(&state), result);
+        }
+
+        state.reduce(action);
+
+        for &result in results.iter().skip(1) {
+            assert_eq!(reactor.react(&state), result);
+        }
+
+        let snapshot = metrics.snapshot();
+        let errors = results.iter().skip(1).filter(|r| r.is_err()).count();
+
+        assert_eq!(snapshot.actions.len(), 1);
+        assert_eq!(snapshot.actions[0].reduce.count, 1);
+        assert_eq!(
+            snapshot.actions[0].react.count,
+            results.len().saturating_sub(1) as u64
+        );
+        assert_eq!(snapshot.actions[0].errors, errors as u64);
+    }
+
+    #[proptest]
+    fn shared(a: u8, b: u16) {
+        let mut x = MockReducer::<u8>::new();
+        x.expect_reduce().return_const(());
+
+        let mut y = MockReducer::<u16>::new();
+        y.expect_reduce().return_const(());
+
+        let mut reactor = MockReactor::<MockReducer<u8>, ()>::new();
+        reactor.expect_react().once().return_const(Ok(()));
+
+        let metrics = Metrics::new();
+        let mut x = metrics.reducer(x);
+        let mut y = metrics.reducer(y);
+        let mut reactor = metrics.reactor(reactor);
+
+        // The reactor is attributed the action its own state reduced last.
+        x.reduce(a);
+        y.reduce(b);
+        assert_eq!(reactor.react(&x), Ok(()));
+
+        let counts: Vec<_> = metrics
+            .snapshot()
+            .actions
+            .iter()
+            .map(|m| (m.action, m.react.count))
+            .collect();
+
+        assert_eq!(counts, [("u16", 0), ("u8", 1)]);
+    }
+
+    #[proptest]
+    fn prometheus(count: u8, #[strategy(0u64..1_000_000)] nanos: u64, errors: u8, depth: usize) {
+        let timing = Timing {
+            count: count.into(),
+            total: Duration::from_nanos(nanos),
+            max: Duration::from_nanos(nanos),
+        };
+
+        let snapshot = Snapshot {
+            elapsed: Duration::ZERO,
+            actions: vec![ActionMetrics {
+                action: "a\"b\\c",
+                reduce: timing,
+                react: timing,
+                errors: errors.into(),
+            }],
+            queue_depth: Some(depth),
+        };
+
+        let mut buffer = Vec::new();
+        snapshot.write_prometheus(&mut buffer)?;
+        let text = String::from_utf8(buffer)?;
+        let seconds = timing.total.as_secs_f64();
+
+        for line in [
+            format!(r#"reducer_actions_total{{action="a\"b\\c"}} {}"#, count),
+            format!(
+                r#"reducer_reduce_seconds_sum{{action="a\"b\\c"}} {}"#,
+                seconds
+            ),
+            format!(
+                r#"reducer_reduce_seconds_count{{action="a\"b\\c"}} {}"#,
+                count
+            ),
+            format!(
+                r#"reducer_react_seconds_max{{action="a\"b\\c"}} {}"#,
+                seconds
+            ),
+            format!(
+                r#"reducer_react_errors_total{{action="a\"b\\c"}} {}"#,
+                errors
+            ),
+            format!("reducer_queue_depth {}", depth),
+        ] {
+            assert!(text.lines().any(|l| l == line), "missing `{}`", line);
+        }
+    }
+}
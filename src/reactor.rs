@@ -1,14 +1,28 @@
 mod array;
 #[cfg(feature = "alloc")]
 mod boxed;
+#[cfg(feature = "async")]
+mod debounce;
+mod diff;
 mod reference;
 #[cfg(feature = "async")]
+mod sample;
+#[cfg(feature = "async")]
 mod sink;
 mod slice;
+#[cfg(feature = "async")]
+mod throttle;
 mod tuple;
 
+#[cfg(feature = "async")]
+pub use debounce::Debounce;
+pub use diff::DiffReactor;
+#[cfg(feature = "async")]
+pub use sample::{Sample, Ticks};
 #[cfg(feature = "async")]
 pub use sink::AsyncReactor;
+#[cfg(feature = "async")]
+pub use throttle::Throttle;
 
 /// Trait for types that react to state transitions.
 ///
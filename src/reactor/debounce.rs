@@ -0,0 +1,240 @@
+use crate::clock::Clock;
+use crate::reactor::sink::release;
+use crate::reactor::*;
+use core::time::Duration;
+use futures::{ready, sink::Sink};
+use pin_project::pin_project;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+/// An adapter that notifies a [`Reactor`] once the state settles (requires [`async`]).
+///
+/// Every state is held back until no other state follows it for a quiet period,
+/// at which point it is notified, so a burst of transitions results in a single notification.
+///
+/// Synchronously, the settled state is notified by [`Debounce::tick`].
+/// [`Debounce::release`] notifies it right away, e.g. before shutting down.
+/// Asynchronously, i.e. if the adapted [`Reactor`] is a [`Sink`],
+/// `poll_flush` waits for the quiet period to elapse and notifies the settled state,
+/// while `poll_close` notifies it right away.
+///
+/// [`async`]: index.html#optional-features
+///
+/// # Example
+///
+/// ```rust
+/// use reducer::*;
+/// use std::time::Duration;
+///
+/// let clock = ManualClock::new();
+/// let (tx, mut rx) = futures::channel::mpsc::unbounded();
+/// let mut debounce = Debounce::new(Duration::from_secs(1), clock.clone(), AsyncReactor(tx));
+///
+/// debounce.react(&"h").unwrap();
+/// debounce.react(&"he").unwrap();
+/// clock.advance(Duration::from_millis(500));
+///
+/// debounce.react(&"hey").unwrap();
+/// clock.advance(Duration::from_millis(500));
+///
+/// // Still typing...
+/// debounce.tick().unwrap();
+/// assert!(rx.try_recv().is_err());
+///
+/// clock.advance(Duration::from_millis(500));
+/// debounce.tick().unwrap();
+///
+/// assert_eq!(rx.try_recv().unwrap(), "hey");
+/// assert!(rx.try_recv().is_err());
+/// ```
+#[pin_project]
+#[derive(Debug)]
+pub struct Debounce<S, R, C: Clock> {
+    #[pin]
+    reactor: R,
+    clock: C,
+    quiet: Duration,
+    last: Option<Instant>,
+    pending: Option<S>,
+    #[pin]
+    sleep: Option<C::Sleep>,
+}
+
+impl<S, R, C: Clock> Debounce<S, R, C> {
+    /// Constructs the Debounce given the quiet period and the [`Clock`] that measures it.
+    pub fn new(quiet: Duration, clock: C, reactor: R) -> Self {
+        Debounce {
+            reactor,
+            clock,
+            quiet,
+            last: None,
+            pending: None,
+            sleep: None,
+        }
+    }
+
+    /// Returns the adapted [`Reactor`], discarding the state held back, if any.
+    pub fn into_inner(self) -> R {
+        self.reactor
+    }
+}
+
+impl<S, R: Reactor<S>, C: Clock> Debounce<S, R, C> {
+    /// Notifies the state held back, if any, once the quiet period has elapsed.
+    pub fn tick(&mut self) -> Result<(), R::Error> {
+        match self.last {
+            Some(last) if self.clock.now() >= last + self.quiet => self.release(),
+            _ => Ok(()),
+        }
+    }
+
+    /// Notifies the state held back, if any, right away.
+    pub fn release(&mut self) -> Result<(), R::Error> {
+        self.last = None;
+
+        match self.pending.take() {
+            None => Ok(()),
+            Some(state) => self.reactor.react(&state),
+        }
+    }
+}
+
+impl<S, R, C> Reactor<S> for Debounce<S, R, C>
+where
+    S: Clone,
+    R: Reactor<S>,
+    C: Clock,
+{
+    type Error = R::Error;
+
+    fn react(&mut self, state: &S) -> Result<(), Self::Error> {
+        self.last = Some(self.clock.now());
+        self.pending = Some(state.clone());
+        Ok(())
+    }
+}
+
+impl<S, R, C, E> Sink<&S> for Debounce<S, R, C>
+where
+    S: Clone,
+    R: for<'s> Sink<&'s S, Error = E>,
+    C: Clock,
+{
+    type Error = E;
+
+    fn poll_ready(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, state: &S) -> Result<(), Self::Error> {
+        let mut this = self.project();
+        *this.last = Some(this.clock.now());
+        *this.pending = Some(state.clone());
+        this.sleep.set(Some(this.clock.sleep(*this.quiet)));
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let mut this = self.project();
+
+        if let Some(sleep) = this.sleep.as_mut().as_pin_mut() {
+            ready!(sleep.poll(cx));
+        }
+
+        if ready!(release(this.reactor.as_mut(), this.pending, cx))? {
+            *this.last = None;
+            this.sleep.set(None);
+        }
+
+        this.reactor.poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let mut this = self.project();
+
+        if ready!(release(this.reactor.as_mut(), this.pending, cx))? {
+            *this.last = None;
+            this.sleep.set(None);
+        }
+
+        this.reactor.poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::reactor::AsyncReactor;
+    use futures::{FutureExt, SinkExt};
+    use std::vec::Vec;
+    use test_strategy::proptest;
+
+    #[proptest]
+    fn react(#[strategy(1u8..)] quiet: u8, steps: Vec<(u8, u8)>) {
+        let clock = ManualClock::new();
+        let quiet = Duration::from_millis(quiet.into());
+        let (tx, mut rx) = futures::channel::mpsc::unbounded();
+        let mut debounce = Debounce::new(quiet, clock.clone(), AsyncReactor(tx));
+
+        let mut pending = None;
+
+        for (step, state) in steps {
+            let step = Duration::from_millis(step.into());
+            clock.advance(step);
+
+            assert_eq!(debounce.tick(), Ok(()));
+
+            if step >= quiet {
+                assert_eq!(rx.try_recv().ok(), pending.take());
+            }
+
+            assert!(rx.try_recv().is_err());
+
+            pending = Some(state);
+            assert_eq!(debounce.react(&state), Ok(()));
+            assert!(rx.try_recv().is_err());
+        }
+
+        assert_eq!(debounce.release(), Ok(()));
+        assert_eq!(rx.try_recv().ok(), pending);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[proptest]
+    fn sink(#[strategy(1u8..)] quiet: u8, states: Vec<u8>) {
+        let clock = ManualClock::new();
+        let quiet = Duration::from_millis(quiet.into());
+        let (tx, mut rx) = futures::channel::mpsc::unbounded();
+        let mut debounce = Debounce::new(quiet, clock.clone(), AsyncReactor(tx));
+
+        for state in &states {
+            assert_eq!(debounce.feed(state).now_or_never(), Some(Ok(())));
+            clock.advance(quiet - Duration::from_millis(1));
+        }
+
+        assert!(rx.try_recv().is_err());
+
+        if !states.is_empty() {
+            assert_eq!(debounce.flush().now_or_never(), None);
+            assert!(rx.try_recv().is_err());
+
+            clock.advance(Duration::from_millis(1));
+            assert_eq!(debounce.flush().now_or_never(), Some(Ok(())));
+            assert_eq!(rx.try_recv().ok(), states.last().copied());
+        }
+
+        for state in &states {
+            assert_eq!(debounce.feed(state).now_or_never(), Some(Ok(())));
+        }
+
+        assert_eq!(debounce.close().now_or_never(), Some(Ok(())));
+        assert_eq!(rx.try_recv().ok(), states.last().copied());
+
+        if !states.is_empty() {
+            assert!(matches!(rx.try_recv(), Err(e) if e.is_closed()));
+        }
+    }
+}
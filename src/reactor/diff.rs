@@ -0,0 +1,130 @@
+use crate::diff::Diff;
+use crate::reactor::*;
+
+/// An adapter that notifies a [`Reactor`] of the [`Diff::Patch`] between the previously
+/// notified state and the current one, rather than the state itself.
+///
+/// The [`Reactor`] is not notified if the state has not changed.
+///
+/// # Example
+///
+#[cfg_attr(feature = "alloc", doc = "```rust")]
+#[cfg_attr(not(feature = "alloc"), doc = "```ignore")]
+/// use reducer::*;
+/// use std::convert::Infallible;
+///
+/// #[derive(Debug, Default, Clone, PartialEq)]
+/// struct Todos(Vec<String>);
+///
+/// struct Add(String);
+///
+/// impl Reducer<Add> for Todos {
+///     fn reduce(&mut self, Add(todo): Add) {
+///         self.0.push(todo);
+///     }
+/// }
+///
+/// impl Diff for Todos {
+///     type Patch = <Vec<String> as Diff>::Patch;
+///
+///     fn diff(&self, other: &Self) -> Option<Self::Patch> {
+///         self.0.diff(&other.0)
+///     }
+///
+///     fn apply(&mut self, patch: Self::Patch) {
+///         self.0.apply(patch)
+///     }
+/// }
+///
+/// struct Log(Vec<String>);
+///
+/// impl Reactor<Vec<VecEdit<String>>> for Log {
+///     type Error = Infallible;
+///
+///     fn react(&mut self, patch: &Vec<VecEdit<String>>) -> Result<(), Self::Error> {
+///         self.0.push(format!("{:?}", patch));
+///         Ok(())
+///     }
+/// }
+///
+/// let mut store = Store::new(Todos::default(), DiffReactor::new(Todos::default(), Log(vec![])));
+///
+/// store.dispatch(Add("write docs".into())).unwrap();
+/// store.dispatch(Add("fix bugs".into())).unwrap();
+///
+/// let log = store.subscribe(DiffReactor::new(Todos::default(), Log(vec![]))).into_inner();
+///
+/// assert_eq!(log.0, [r#"[Push("write docs")]"#, r#"[Push("fix bugs")]"#]);
+/// ```
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct DiffReactor<S, R> {
+    previous: S,
+    reactor: R,
+}
+
+impl<S, R> DiffReactor<S, R> {
+    /// Constructs the DiffReactor given the state the first notification is relative to.
+    pub fn new(state: S, reactor: R) -> Self {
+        DiffReactor {
+            previous: state,
+            reactor,
+        }
+    }
+
+    /// Returns the adapted [`Reactor`].
+    pub fn into_inner(self) -> R {
+        self.reactor
+    }
+}
+
+impl<S, R> Reactor<S> for DiffReactor<S, R>
+where
+    S: Diff,
+    R: Reactor<S::Patch>,
+{
+    type Error = R::Error;
+
+    fn react(&mut self, state: &S) -> Result<(), Self::Error> {
+        match self.previous.diff(state) {
+            None => Ok(()),
+            Some(patch) => {
+                let result = self.reactor.react(&patch);
+                self.previous.apply(patch);
+                result
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_strategy::proptest;
+
+    #[proptest]
+    fn react(states: [u8; 4], result: Result<(), u8>) {
+        let changes = states
+            .iter()
+            .scan(0, |previous, &state| {
+                Some(state != core::mem::replace(previous, state))
+            })
+            .filter(|&changed| changed)
+            .count();
+
+        let mut mock = MockReactor::<u8, u8>::new();
+        mock.expect_react().times(changes).return_const(result);
+
+        let mut reactor = DiffReactor::new(0, mock);
+
+        for state in states {
+            let expected = if state != reactor.previous {
+                result
+            } else {
+                Ok(())
+            };
+
+            assert_eq!(reactor.react(&state), expected);
+            assert_eq!(reactor.previous, state);
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use crate::reactor::*;
 
 /// Forwards the event to a potentially stack allocated [`Reactor`].
-impl<'a, S, T> Reactor<S> for &'a mut T
+impl<S, T> Reactor<S> for &mut T
 where
     S: ?Sized,
     T: Reactor<S> + ?Sized,
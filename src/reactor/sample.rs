@@ -0,0 +1,329 @@
+use crate::clock::Clock;
+use crate::reactor::sink::release;
+use crate::reactor::*;
+use core::time::Duration;
+use futures::stream::{FusedStream, Stream};
+use futures::{ready, sink::Sink};
+use pin_project::pin_project;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+/// An adapter that notifies a [`Reactor`] of the latest state on every tick
+/// (requires [`async`]).
+///
+/// States are held back until the next tick, which notifies the latest one, if any.
+///
+/// Synchronously, ticks are signaled by [`Sample::tick`].
+/// Asynchronously, i.e. if the adapted [`Reactor`] is a [`Sink`],
+/// ticks are the items of a [`FusedStream`], such as [`Ticks`] or any [fused] [`Stream`],
+/// and `poll_flush` waits for the next tick and notifies the latest state,
+/// while `poll_close` notifies it right away.
+///
+/// [`async`]: index.html#optional-features
+/// [fused]: futures::stream::StreamExt::fuse
+///
+/// # Example
+///
+/// ```rust
+/// use reducer::*;
+///
+/// let (tx, mut rx) = futures::channel::mpsc::unbounded();
+/// let mut sample = Sample::new((), AsyncReactor(tx));
+///
+/// sample.react(&1).unwrap();
+/// sample.react(&2).unwrap();
+/// sample.tick().unwrap();
+///
+/// sample.tick().unwrap();
+///
+/// sample.react(&3).unwrap();
+/// sample.tick().unwrap();
+///
+/// assert_eq!(rx.try_recv().unwrap(), 2);
+/// assert_eq!(rx.try_recv().unwrap(), 3);
+/// assert!(rx.try_recv().is_err());
+/// ```
+#[pin_project]
+#[derive(Debug)]
+pub struct Sample<S, R, T> {
+    #[pin]
+    reactor: R,
+    #[pin]
+    ticks: T,
+    pending: Option<S>,
+}
+
+impl<S, R, T> Sample<S, R, T> {
+    /// Constructs the Sample given the [`Stream`] of ticks.
+    pub fn new(ticks: T, reactor: R) -> Self {
+        Sample {
+            reactor,
+            ticks,
+            pending: None,
+        }
+    }
+
+    /// Returns the adapted [`Reactor`], discarding the state held back, if any.
+    pub fn into_inner(self) -> R {
+        self.reactor
+    }
+}
+
+impl<S, R: Reactor<S>, T> Sample<S, R, T> {
+    /// Notifies the state held back, if any.
+    pub fn tick(&mut self) -> Result<(), R::Error> {
+        match self.pending.take() {
+            None => Ok(()),
+            Some(state) => self.reactor.react(&state),
+        }
+    }
+}
+
+impl<S, R, T> Reactor<S> for Sample<S, R, T>
+where
+    S: Clone,
+    R: Reactor<S>,
+{
+    type Error = R::Error;
+
+    fn react(&mut self, state: &S) -> Result<(), Self::Error> {
+        self.pending = Some(state.clone());
+        Ok(())
+    }
+}
+
+impl<S, R, T, E> Sink<&S> for Sample<S, R, T>
+where
+    S: Clone,
+    R: for<'s> Sink<&'s S, Error = E>,
+    T: FusedStream,
+{
+    type Error = E;
+
+    fn poll_ready(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, state: &S) -> Result<(), Self::Error> {
+        *self.project().pending = Some(state.clone());
+        Ok(())
+    }
+
+    /// Once the stream of ticks terminates, the state is notified right away.
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let mut this = self.project();
+
+        // Ticks are only consumed once the reactor is ready to be notified.
+        if this.pending.is_some() {
+            ready!(this.reactor.as_mut().poll_ready(cx))?;
+            ready!(this.ticks.poll_next(cx));
+        }
+
+        ready!(release(this.reactor.as_mut(), this.pending, cx))?;
+        this.reactor.poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let mut this = self.project();
+
+        ready!(release(this.reactor.as_mut(), this.pending, cx))?;
+        this.reactor.poll_close(cx)
+    }
+}
+
+/// A [`Stream`] that ticks periodically, as measured by a [`Clock`] (requires [`async`]).
+///
+/// Ticks missed because the stream wasn't polled in time are skipped.
+///
+/// [`async`]: index.html#optional-features
+///
+/// # Example
+///
+/// ```rust
+/// use reducer::*;
+/// use futures::prelude::*;
+/// use std::time::Duration;
+///
+/// let clock = ManualClock::new();
+/// let mut ticks = Ticks::new(Duration::from_secs(1), clock.clone());
+/// assert_eq!(ticks.next().now_or_never(), None);
+///
+/// clock.advance(Duration::from_secs(1));
+/// assert_eq!(ticks.next().now_or_never(), Some(Some(())));
+/// assert_eq!(ticks.next().now_or_never(), None);
+///
+/// clock.advance(Duration::from_secs(3));
+/// assert_eq!(ticks.next().now_or_never(), Some(Some(())));
+/// assert_eq!(ticks.next().now_or_never(), None);
+/// ```
+#[pin_project]
+#[derive(Debug)]
+pub struct Ticks<C: Clock> {
+    clock: C,
+    period: Duration,
+    deadline: Instant,
+    #[pin]
+    sleep: C::Sleep,
+}
+
+impl<C: Clock> Ticks<C> {
+    /// Constructs the Ticks given the period and the [`Clock`] that measures it.
+    pub fn new(period: Duration, clock: C) -> Self {
+        Ticks {
+            deadline: clock.now() + period,
+            sleep: clock.sleep(period),
+            clock,
+            period,
+        }
+    }
+}
+
+impl<C: Clock> Stream for Ticks<C> {
+    type Item = ();
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<()>> {
+        let mut this = self.project();
+        ready!(this.sleep.as_mut().poll(cx));
+
+        let now = this.clock.now();
+        *this.deadline += *this.period;
+
+        if *this.deadline <= now {
+            *this.deadline = now + *this.period;
+        }
+
+        this.sleep.set(this.clock.sleep(*this.deadline - now));
+        Poll::Ready(Some(()))
+    }
+}
+
+impl<C: Clock> FusedStream for Ticks<C> {
+    /// [`Ticks`] never terminates.
+    fn is_terminated(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::reactor::AsyncReactor;
+    use futures::{FutureExt, SinkExt, StreamExt};
+    use std::vec::Vec;
+    use test_strategy::proptest;
+
+    #[proptest]
+    fn react(steps: Vec<Option<u8>>) {
+        let (tx, mut rx) = futures::channel::mpsc::unbounded();
+        let mut sample = Sample::new((), AsyncReactor(tx));
+
+        let mut pending = None;
+
+        for step in steps {
+            match step {
+                Some(state) => {
+                    pending = Some(state);
+                    assert_eq!(sample.react(&state), Ok(()));
+                }
+
+                None => {
+                    assert_eq!(sample.tick(), Ok(()));
+                    assert_eq!(rx.try_recv().ok(), pending.take());
+                }
+            }
+
+            assert!(rx.try_recv().is_err());
+        }
+    }
+
+    #[proptest]
+    fn sink(#[strategy(1u8..)] period: u8, states: Vec<u8>) {
+        let clock = ManualClock::new();
+        let period = Duration::from_millis(period.into());
+        let (tx, mut rx) = futures::channel::mpsc::unbounded();
+        let ticks = Ticks::new(period, clock.clone());
+        let mut sample = Sample::new(ticks, AsyncReactor(tx));
+
+        for state in &states {
+            assert_eq!(sample.feed(state).now_or_never(), Some(Ok(())));
+        }
+
+        if !states.is_empty() {
+            assert_eq!(sample.flush().now_or_never(), None);
+            assert!(rx.try_recv().is_err());
+
+            clock.advance(period);
+            assert_eq!(sample.flush().now_or_never(), Some(Ok(())));
+            assert_eq!(rx.try_recv().ok(), states.last().copied());
+        }
+
+        assert_eq!(sample.flush().now_or_never(), Some(Ok(())));
+
+        for state in &states {
+            assert_eq!(sample.feed(state).now_or_never(), Some(Ok(())));
+        }
+
+        assert_eq!(sample.close().now_or_never(), Some(Ok(())));
+        assert_eq!(rx.try_recv().ok(), states.last().copied());
+
+        if !states.is_empty() {
+            assert!(matches!(rx.try_recv(), Err(e) if e.is_closed()));
+        }
+    }
+
+    #[proptest]
+    fn terminated(#[strategy(0usize..8)] ticks: usize, states: Vec<u8>) {
+        let mut remaining = Some(ticks);
+        let ticks = futures::stream::poll_fn(move |_| match remaining.as_mut() {
+            None => panic!("ticks polled after terminating"),
+            Some(0) => {
+                remaining = None;
+                Poll::Ready(None)
+            }
+            Some(n) => {
+                *n -= 1;
+                Poll::Ready(Some(()))
+            }
+        });
+
+        let (tx, mut rx) = futures::channel::mpsc::unbounded();
+        let mut sample = Sample::new(ticks.fuse(), AsyncReactor(tx));
+
+        // Once the ticks run out, states are notified right away.
+        for state in &states {
+            assert_eq!(sample.feed(state).now_or_never(), Some(Ok(())));
+            assert_eq!(sample.flush().now_or_never(), Some(Ok(())));
+            assert_eq!(rx.try_recv().ok(), Some(*state));
+        }
+    }
+
+    #[proptest]
+    fn ticks(#[strategy(1u8..)] period: u8, steps: Vec<u8>) {
+        let clock = ManualClock::new();
+        let period = Duration::from_millis(period.into());
+        let mut ticks = Ticks::new(period, clock.clone());
+
+        let mut elapsed = Duration::ZERO;
+        let mut deadline = period;
+
+        for step in steps {
+            let step = Duration::from_millis(step.into());
+            clock.advance(step);
+            elapsed += step;
+
+            if elapsed >= deadline {
+                assert_eq!(ticks.next().now_or_never(), Some(Some(())));
+                deadline += period;
+
+                if deadline <= elapsed {
+                    deadline = elapsed + period;
+                }
+            }
+
+            assert_eq!(ticks.next().now_or_never(), None);
+        }
+    }
+}
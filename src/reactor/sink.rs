@@ -1,5 +1,6 @@
 use crate::reactor::*;
 use derive_more::{Deref, DerefMut, From};
+use futures::ready;
 use futures::sink::{Sink, SinkExt};
 use pin_project::pin_project;
 use std::task::{Context, Poll};
@@ -72,6 +73,28 @@ where
     }
 }
 
+/// Notifies `reactor` of the state held back, if any, returning whether it was notified.
+///
+/// Shared by the adapters that hold back states, once they decide to let the latest one through.
+pub(crate) fn release<S, R, E>(
+    mut reactor: Pin<&mut R>,
+    pending: &mut Option<S>,
+    cx: &mut Context<'_>,
+) -> Poll<Result<bool, E>>
+where
+    R: for<'s> Sink<&'s S, Error = E>,
+{
+    match pending.as_ref() {
+        None => Poll::Ready(Ok(false)),
+        Some(state) => {
+            ready!(reactor.as_mut().poll_ready(cx))?;
+            reactor.start_send(state)?;
+            *pending = None;
+            Poll::Ready(Ok(true))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,269 @@
+use crate::clock::Clock;
+use crate::reactor::sink::release;
+use crate::reactor::*;
+use core::time::Duration;
+use futures::{ready, sink::Sink};
+use pin_project::pin_project;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+/// An adapter that notifies a [`Reactor`] at most once per interval (requires [`async`]).
+///
+/// The first state is notified right away, subsequent ones are held back until the interval
+/// has elapsed since the last notification, at which point the latest state is notified.
+///
+/// Synchronously, the latest state is notified either by the next call to [`Reactor::react`]
+/// once the interval has elapsed, or by [`Throttle::tick`].
+/// [`Throttle::release`] notifies it right away, e.g. before shutting down.
+/// Asynchronously, i.e. if the adapted [`Reactor`] is a [`Sink`],
+/// `poll_flush` waits until the interval has elapsed and notifies the latest state,
+/// while `poll_close` notifies it right away.
+///
+/// [`async`]: index.html#optional-features
+///
+/// # Example
+///
+/// ```rust
+/// use reducer::*;
+/// use std::time::Duration;
+///
+/// let clock = ManualClock::new();
+/// let (tx, mut rx) = futures::channel::mpsc::unbounded();
+/// let mut throttle = Throttle::new(Duration::from_secs(1), clock.clone(), AsyncReactor(tx));
+///
+/// throttle.react(&1).unwrap();
+/// throttle.react(&2).unwrap();
+/// throttle.react(&3).unwrap();
+///
+/// clock.advance(Duration::from_secs(1));
+/// throttle.tick().unwrap();
+///
+/// assert_eq!(rx.try_recv().unwrap(), 1);
+/// assert_eq!(rx.try_recv().unwrap(), 3);
+/// assert!(rx.try_recv().is_err());
+/// ```
+#[pin_project]
+#[derive(Debug)]
+pub struct Throttle<S, R, C: Clock> {
+    #[pin]
+    reactor: R,
+    clock: C,
+    interval: Duration,
+    last: Option<Instant>,
+    pending: Option<S>,
+    #[pin]
+    sleep: Option<C::Sleep>,
+}
+
+impl<S, R, C: Clock> Throttle<S, R, C> {
+    /// Constructs the Throttle given the minimum interval between notifications and the
+    /// [`Clock`] that measures it.
+    pub fn new(interval: Duration, clock: C, reactor: R) -> Self {
+        Throttle {
+            reactor,
+            clock,
+            interval,
+            last: None,
+            pending: None,
+            sleep: None,
+        }
+    }
+
+    /// Returns the adapted [`Reactor`], discarding the state held back, if any.
+    pub fn into_inner(self) -> R {
+        self.reactor
+    }
+
+    fn is_due(&self, now: Instant) -> bool {
+        match self.last {
+            Some(last) => now >= last + self.interval,
+            None => true,
+        }
+    }
+}
+
+impl<S, R: Reactor<S>, C: Clock> Throttle<S, R, C> {
+    /// Notifies the state held back, if any, once the interval has elapsed.
+    pub fn tick(&mut self) -> Result<(), R::Error> {
+        if self.is_due(self.clock.now()) {
+            self.release()
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Notifies the state held back, if any, right away.
+    pub fn release(&mut self) -> Result<(), R::Error> {
+        match self.pending.take() {
+            None => Ok(()),
+            Some(state) => {
+                self.last = Some(self.clock.now());
+                self.reactor.react(&state)
+            }
+        }
+    }
+}
+
+impl<S, R, C> Reactor<S> for Throttle<S, R, C>
+where
+    S: Clone,
+    R: Reactor<S>,
+    C: Clock,
+{
+    type Error = R::Error;
+
+    fn react(&mut self, state: &S) -> Result<(), Self::Error> {
+        let now = self.clock.now();
+
+        if self.is_due(now) {
+            self.pending = None;
+            self.last = Some(now);
+            self.reactor.react(state)
+        } else {
+            self.pending = Some(state.clone());
+            Ok(())
+        }
+    }
+}
+
+impl<S, R, C, E> Sink<&S> for Throttle<S, R, C>
+where
+    S: Clone,
+    R: for<'s> Sink<&'s S, Error = E>,
+    C: Clock,
+{
+    type Error = E;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.project().reactor.poll_ready(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, state: &S) -> Result<(), Self::Error> {
+        let now = self.clock.now();
+
+        if self.is_due(now) {
+            let mut this = self.project();
+            *this.pending = None;
+            *this.last = Some(now);
+            this.sleep.set(None);
+            this.reactor.start_send(state)
+        } else {
+            let deadline = self.last.map_or(now, |last| last + self.interval);
+            let mut this = self.project();
+
+            if this.sleep.is_none() {
+                this.sleep.set(Some(this.clock.sleep(deadline - now)));
+            }
+
+            *this.pending = Some(state.clone());
+            Ok(())
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let mut this = self.project();
+
+        if let Some(sleep) = this.sleep.as_mut().as_pin_mut() {
+            ready!(sleep.poll(cx));
+        }
+
+        if ready!(release(this.reactor.as_mut(), this.pending, cx))? {
+            *this.last = Some(this.clock.now());
+            this.sleep.set(None);
+        }
+
+        this.reactor.poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let mut this = self.project();
+
+        if ready!(release(this.reactor.as_mut(), this.pending, cx))? {
+            *this.last = Some(this.clock.now());
+            this.sleep.set(None);
+        }
+
+        this.reactor.poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::reactor::AsyncReactor;
+    use futures::{FutureExt, SinkExt};
+    use std::vec::Vec;
+    use test_strategy::proptest;
+
+    #[proptest]
+    fn react(#[strategy(1u8..)] interval: u8, steps: Vec<(u8, u8)>) {
+        let clock = ManualClock::new();
+        let interval = Duration::from_millis(interval.into());
+        let (tx, mut rx) = futures::channel::mpsc::unbounded();
+        let mut throttle = Throttle::new(interval, clock.clone(), AsyncReactor(tx));
+
+        let mut elapsed = Duration::ZERO;
+        let mut last: Option<Duration> = None;
+        let mut pending = None;
+
+        for (step, state) in steps {
+            let step = Duration::from_millis(step.into());
+            clock.advance(step);
+            elapsed += step;
+
+            if last.is_none_or(|last| elapsed >= last + interval) {
+                last = Some(elapsed);
+                pending = None;
+                assert_eq!(throttle.react(&state), Ok(()));
+                assert_eq!(rx.try_recv().ok(), Some(state));
+            } else {
+                pending = Some(state);
+                assert_eq!(throttle.react(&state), Ok(()));
+                assert!(rx.try_recv().is_err());
+
+                assert_eq!(throttle.tick(), Ok(()));
+                assert!(rx.try_recv().is_err());
+            }
+        }
+
+        assert_eq!(throttle.release(), Ok(()));
+        assert_eq!(rx.try_recv().ok(), pending);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[proptest]
+    fn sink(#[strategy(1u8..)] interval: u8, states: Vec<u8>) {
+        let clock = ManualClock::new();
+        let interval = Duration::from_millis(interval.into());
+        let (tx, mut rx) = futures::channel::mpsc::unbounded();
+        let mut throttle = Throttle::new(interval, clock.clone(), AsyncReactor(tx));
+
+        for state in &states {
+            assert_eq!(throttle.feed(state).now_or_never(), Some(Ok(())));
+        }
+
+        assert_eq!(rx.try_recv().ok(), states.first().copied());
+
+        if states.len() > 1 {
+            assert_eq!(throttle.flush().now_or_never(), None);
+            assert!(rx.try_recv().is_err());
+
+            clock.advance(interval);
+            assert_eq!(throttle.flush().now_or_never(), Some(Ok(())));
+            assert_eq!(rx.try_recv().ok(), states.last().copied());
+
+            for state in &states {
+                assert_eq!(throttle.feed(state).now_or_never(), Some(Ok(())));
+            }
+        }
+
+        assert_eq!(throttle.close().now_or_never(), Some(Ok(())));
+
+        let expected = states.get(1..).and_then(|s| s.last().copied());
+        assert_eq!(rx.try_recv().ok(), expected);
+        assert!(matches!(rx.try_recv(), Err(e) if e.is_closed()));
+    }
+}
@@ -2,10 +2,29 @@
 mod arc;
 #[cfg(feature = "alloc")]
 mod boxed;
+#[cfg(feature = "async")]
+mod effect;
+#[cfg(feature = "std")]
+mod meta;
+#[cfg(feature = "alloc")]
+mod optimistic;
 #[cfg(feature = "alloc")]
 mod rc;
 mod tuple;
 
+#[cfg(feature = "async")]
+pub use self::effect::{CancellationToken, Effect, EffectReducer};
+#[cfg(feature = "std")]
+pub use self::meta::*;
+#[cfg(feature = "alloc")]
+pub use self::optimistic::*;
+
+#[cfg(feature = "async")]
+pub(crate) use self::effect::Effects;
+
+#[cfg(all(test, feature = "async"))]
+pub(crate) use self::effect::MockEffectReducer;
+
 /// Trait for types that represent the logical state of an application.
 ///
 /// Perhaps a more accurate mental model for types that implement this trait is that of a
@@ -1,7 +1,7 @@
 use crate::reducer::*;
 use alloc::sync::Arc;
 
-/// Enhances a potentially _unsized_ [`Reducer`] with copy-on-write semantics (requires [`alloc`]).
+/// Enhances a [`Reducer`] with copy-on-write semantics (requires [`alloc`]).
 ///
 /// Helps avoiding cloning the entire state when it needs to be sent to other threads,
 /// e.g to the rendering thread of a GUI.
@@ -45,7 +45,7 @@ use alloc::sync::Arc;
 /// ```
 impl<A, T> Reducer<A> for Arc<T>
 where
-    T: Reducer<A> + Clone + ?Sized,
+    T: Reducer<A> + Clone,
 {
     fn reduce(&mut self, action: A) {
         Arc::make_mut(self).reduce(action);
@@ -0,0 +1,465 @@
+use futures::stream::{self, Fuse, SelectAll, Stream, StreamExt};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::mem::take;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll, Waker};
+use std::{boxed::Box, pin::Pin, vec::Vec};
+
+type Running<A> = Pin<Box<dyn Stream<Item = A> + Send>>;
+
+/// Trait for types that represent the logical state of an application and
+/// describe side effects as values (requires [`async`]).
+///
+/// Much like [`Reducer::reduce`](crate::Reducer::reduce), [`EffectReducer::reduce`] is expected
+/// to have no side effects itself, instead it returns an [`Effect`] that describes them,
+/// which is then executed by the [spawned] [`Store`](crate::Store).
+/// Actions produced by effects are fed back into the [`Store`](crate::Store).
+///
+/// [spawned]: crate::Store::into_effect_task
+/// [`async`]: index.html#optional-features
+///
+/// # Example
+///
+/// ```rust
+/// use reducer::*;
+///
+/// #[derive(Default)]
+/// struct Profile {
+///     name: Option<String>,
+///     request: CancellationToken,
+/// }
+///
+/// enum Action {
+///     Load(u32),
+///     Loaded(String),
+/// }
+///
+/// async fn fetch(id: u32) -> String {
+///     // ...
+///     # String::new()
+/// }
+///
+/// impl EffectReducer<Action> for Profile {
+///     fn reduce(&mut self, action: Action) -> Effect<Action> {
+///         match action {
+///             Action::Load(id) => Effect::Batch(vec![
+///                 // Cancel the pending request, if any.
+///                 Effect::Cancel(self.request.clone()),
+///                 Effect::future(async move { Action::Loaded(fetch(id).await) })
+///                     .cancellable(&self.request),
+///             ]),
+///
+///             Action::Loaded(name) => {
+///                 self.name = Some(name);
+///                 Effect::None
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub trait EffectReducer<A> {
+    /// Implements the transition given the current state and an action,
+    /// returning the [`Effect`] to be executed.
+    fn reduce(&mut self, action: A) -> Effect<A>;
+}
+
+/// A description of a side effect that produces further actions (requires [`async`]).
+///
+/// [`async`]: index.html#optional-features
+#[derive(Default)]
+pub enum Effect<A> {
+    /// No side effect.
+    #[default]
+    None,
+
+    /// A future that resolves to an action.
+    Future(Pin<Box<dyn Future<Output = A> + Send>>),
+
+    /// A stream of actions.
+    Stream(Pin<Box<dyn Stream<Item = A> + Send>>),
+
+    /// Cancels all effects associated with a [`CancellationToken`] that are still running.
+    Cancel(CancellationToken),
+
+    /// An effect that may be cancelled through a [`CancellationToken`].
+    Cancellable(CancellationToken, Box<Effect<A>>),
+
+    /// Several effects executed concurrently.
+    Batch(Vec<Effect<A>>),
+}
+
+impl<A> Effect<A> {
+    /// Constructs an [`Effect::Future`].
+    pub fn future(future: impl Future<Output = A> + Send + 'static) -> Self {
+        Effect::Future(Box::pin(future))
+    }
+
+    /// Constructs an [`Effect::Stream`].
+    pub fn stream(stream: impl Stream<Item = A> + Send + 'static) -> Self {
+        Effect::Stream(Box::pin(stream))
+    }
+
+    /// Associates this effect with a [`CancellationToken`].
+    pub fn cancellable(self, token: &CancellationToken) -> Self {
+        Effect::Cancellable(token.clone(), Box::new(self))
+    }
+}
+
+impl<A> core::fmt::Debug for Effect<A> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Effect::None => f.write_str("None"),
+            Effect::Future(_) => f.write_str("Future"),
+            Effect::Stream(_) => f.write_str("Stream"),
+            Effect::Cancel(token) => f.debug_tuple("Cancel").field(token).finish(),
+            Effect::Cancellable(token, effect) => f
+                .debug_tuple("Cancellable")
+                .field(token)
+                .field(effect)
+                .finish(),
+            Effect::Batch(effects) => f.debug_tuple("Batch").field(effects).finish(),
+        }
+    }
+}
+
+/// Identifies a group of [cancellable] [`Effect`]s (requires [`async`]).
+///
+/// Clones refer to the same group of effects.
+/// A token may be reused after its effects are [cancelled].
+///
+/// [cancellable]: Effect::cancellable
+/// [cancelled]: Effect::Cancel
+/// [`async`]: index.html#optional-features
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<Mutex<Cancellation>>);
+
+#[derive(Debug, Default)]
+struct Cancellation {
+    generation: usize,
+    next: usize,
+    wakers: BTreeMap<usize, Waker>,
+}
+
+impl CancellationToken {
+    /// Constructs a [`CancellationToken`] not yet associated with any [`Effect`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Cancellation> {
+        // The shared state is always left consistent, so it's safe to recover from poisoning.
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn cancel(&self) {
+        let mut cancellation = self.lock();
+        cancellation.generation = cancellation.generation.wrapping_add(1);
+        let wakers = take(&mut cancellation.wakers);
+        drop(cancellation);
+
+        wakers.into_values().for_each(Waker::wake);
+    }
+}
+
+/// A stream that terminates early once its [`CancellationToken`] is cancelled.
+struct Cancellable<A> {
+    stream: Running<A>,
+    token: CancellationToken,
+    generation: usize,
+    id: usize,
+}
+
+impl<A> Cancellable<A> {
+    fn new(stream: Running<A>, token: CancellationToken) -> Self {
+        let mut cancellation = token.lock();
+        let generation = cancellation.generation;
+        let id = cancellation.next;
+        cancellation.next = id.wrapping_add(1);
+        drop(cancellation);
+
+        Cancellable {
+            stream,
+            token,
+            generation,
+            id,
+        }
+    }
+}
+
+impl<A> Stream for Cancellable<A> {
+    type Item = A;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let mut cancellation = this.token.lock();
+
+        if cancellation.generation != this.generation {
+            return Poll::Ready(None);
+        }
+
+        cancellation.wakers.insert(this.id, cx.waker().clone());
+        drop(cancellation);
+
+        this.stream.poll_next_unpin(cx)
+    }
+}
+
+impl<A> Drop for Cancellable<A> {
+    fn drop(&mut self) {
+        self.token.lock().wakers.remove(&self.id);
+    }
+}
+
+/// Merges a stream of dispatched actions with the actions produced by running effects.
+///
+/// Terminates once `actions` terminates and there are no running effects.
+pub(crate) struct Effects<T, A> {
+    actions: Fuse<T>,
+    running: SelectAll<Running<A>>,
+    running_first: bool,
+}
+
+impl<T, A> Effects<T, A>
+where
+    T: Stream<Item = A>,
+    A: Send + 'static,
+{
+    pub(crate) fn new(actions: T) -> Self {
+        Effects {
+            actions: actions.fuse(),
+            running: SelectAll::new(),
+            running_first: false,
+        }
+    }
+
+    /// Starts executing an [`Effect`].
+    pub(crate) fn run(&mut self, effect: Effect<A>) {
+        self.spawn(effect, &mut Vec::new());
+    }
+
+    fn spawn(&mut self, effect: Effect<A>, tokens: &mut Vec<CancellationToken>) {
+        let mut running: Running<A> = match effect {
+            Effect::None => return,
+            Effect::Future(future) => Box::pin(stream::once(future)),
+            Effect::Stream(stream) => stream,
+            Effect::Cancel(token) => return token.cancel(),
+
+            Effect::Cancellable(token, effect) => {
+                tokens.push(token);
+                self.spawn(*effect, tokens);
+                tokens.pop();
+                return;
+            }
+
+            Effect::Batch(effects) => {
+                for effect in effects {
+                    self.spawn(effect, tokens);
+                }
+
+                return;
+            }
+        };
+
+        for token in tokens.iter() {
+            running = Box::pin(Cancellable::new(running, token.clone()));
+        }
+
+        self.running.push(running);
+    }
+}
+
+impl<T, A> Stream for Effects<T, A>
+where
+    T: Stream<Item = A> + Unpin,
+{
+    type Item = A;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        // Alternate between sources, so neither starves the other.
+        this.running_first = !this.running_first;
+
+        let (running, actions) = if this.running_first {
+            let running = this.running.poll_next_unpin(cx);
+            if let Poll::Ready(Some(action)) = running {
+                return Poll::Ready(Some(action));
+            }
+
+            (running, this.actions.poll_next_unpin(cx))
+        } else {
+            let actions = this.actions.poll_next_unpin(cx);
+            if let Poll::Ready(Some(action)) = actions {
+                return Poll::Ready(Some(action));
+            }
+
+            (this.running.poll_next_unpin(cx), actions)
+        };
+
+        match (running, actions) {
+            (Poll::Ready(Some(action)), _) | (_, Poll::Ready(Some(action))) => {
+                Poll::Ready(Some(action))
+            }
+
+            (Poll::Ready(None), Poll::Ready(None)) => Poll::Ready(None),
+            _ => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::channel::{mpsc, oneshot};
+    use futures::{executor::block_on, FutureExt};
+    use mockall::*;
+    use std::vec;
+    use test_strategy::proptest;
+
+    mock! {
+        pub EffectReducer<A: 'static> {
+            pub fn id(&self) -> usize;
+        }
+
+        impl<A: 'static> EffectReducer<A> for EffectReducer<A> {
+            fn reduce(&mut self, action: A) -> Effect<A>;
+        }
+
+        impl<A: 'static> Clone for EffectReducer<A> {
+            fn clone(&self) -> Self;
+        }
+    }
+
+    #[proptest]
+    fn reduce(action: u8, result: u8) {
+        let mut mock = MockEffectReducer::new();
+
+        mock.expect_reduce()
+            .with(predicate::eq(action))
+            .once()
+            .returning(move |_| Effect::future(async move { result }));
+
+        let reducer: &mut dyn EffectReducer<_> = &mut mock;
+        let mut effects = Effects::new(stream::empty());
+        effects.run(reducer.reduce(action));
+        assert_eq!(block_on(effects.collect::<Vec<_>>()), [result]);
+    }
+
+    #[proptest]
+    fn none(actions: Vec<u8>) {
+        let mut effects = Effects::new(stream::iter(actions.clone()));
+        effects.run(Effect::None);
+        assert_eq!(block_on(effects.collect::<Vec<_>>()), actions);
+    }
+
+    #[proptest]
+    fn stream(actions: Vec<u8>, results: Vec<u8>) {
+        let mut effects = Effects::new(stream::iter(actions.clone()));
+        effects.run(Effect::stream(stream::iter(results.clone())));
+
+        let mut output = block_on(effects.collect::<Vec<_>>());
+        let mut expected: Vec<_> = actions.iter().chain(&results).copied().collect();
+        output.sort_unstable();
+        expected.sort_unstable();
+        assert_eq!(output, expected);
+    }
+
+    #[proptest]
+    fn batch(results: Vec<u8>) {
+        let mut effects = Effects::new(stream::empty());
+        effects.run(Effect::Batch(
+            results
+                .iter()
+                .map(|&r| Effect::future(async move { r }))
+                .collect(),
+        ));
+
+        let mut output = block_on(effects.collect::<Vec<_>>());
+        let mut expected = results.clone();
+        output.sort_unstable();
+        expected.sort_unstable();
+        assert_eq!(output, expected);
+    }
+
+    #[proptest]
+    fn cancel(a: u8, b: u8) {
+        let (tx, rx) = oneshot::channel();
+        let token = CancellationToken::new();
+        let mut effects = Effects::new(stream::pending());
+
+        effects.run(Effect::future(rx.map(Result::unwrap)).cancellable(&token));
+        effects.run(Effect::future(async move { b }));
+        effects.run(Effect::Cancel(token.clone()));
+
+        assert_eq!(block_on(effects.next()), Some(b));
+        assert_eq!(tx.send(a), Err(a));
+        assert_eq!(effects.next().now_or_never(), None);
+
+        // The token may be reused.
+        effects.run(Effect::future(async move { a }).cancellable(&token));
+        assert_eq!(block_on(effects.next()), Some(a));
+        assert_eq!(effects.next().now_or_never(), None);
+        assert!(token.lock().wakers.is_empty());
+    }
+
+    #[proptest]
+    fn nested(a: u8) {
+        let outer = CancellationToken::new();
+        let inner = CancellationToken::new();
+        let (tx, rx) = mpsc::unbounded::<u8>();
+        let mut effects = Effects::new(stream::pending());
+
+        effects
+            .run(Effect::Batch(vec![Effect::stream(rx).cancellable(&inner)]).cancellable(&outer));
+        tx.unbounded_send(a)?;
+        assert_eq!(block_on(effects.next()), Some(a));
+
+        effects.run(Effect::Cancel(outer));
+        assert_eq!(effects.next().now_or_never(), None);
+        assert!(tx.unbounded_send(a).is_err());
+    }
+
+    #[proptest]
+    fn terminates(actions: Vec<u8>) {
+        let (tx, rx) = oneshot::channel();
+        let mut effects = Effects::new(stream::iter(actions.clone()));
+        effects.run(Effect::future(rx.map(Result::unwrap)));
+
+        let mut output: Vec<_> = (&mut effects)
+            .take(actions.len())
+            .collect::<Vec<_>>()
+            .now_or_never()
+            .unwrap();
+        assert_eq!(output, actions);
+
+        // Still waiting for the running effect.
+        assert_eq!(effects.next().now_or_never(), None);
+
+        tx.send(0).unwrap();
+        output = block_on(effects.collect::<Vec<_>>());
+        assert_eq!(output, [0]);
+    }
+
+    #[proptest]
+    fn debug() {
+        let token = CancellationToken::new();
+        let effect = Effect::Batch(vec![
+            Effect::None,
+            Effect::future(async { 0u8 }),
+            Effect::stream(stream::empty()),
+            Effect::Cancel(token.clone()),
+        ]);
+
+        assert_eq!(
+            format!("{:?}", effect.cancellable(&token)),
+            format!(
+                "Cancellable({:?}, Batch([None, Future, Stream, Cancel({:?})]))",
+                token, token
+            )
+        );
+    }
+}
+
+#[cfg(test)]
+pub(crate) use self::tests::MockEffectReducer;
@@ -0,0 +1,281 @@
+use crate::reducer::*;
+use derive_more::{Deref, DerefMut};
+use std::borrow::Cow;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
+
+static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// Information about an action, such as when and where it originated (requires [`std`]).
+///
+/// [`std`]: index.html#optional-features
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Metadata {
+    sequence: u64,
+    timestamp: SystemTime,
+    source: Option<Cow<'static, str>>,
+    correlation: Option<u64>,
+}
+
+impl Metadata {
+    /// The position of the action in the order actions were created within this process.
+    ///
+    /// Sequence numbers are unique and strictly increasing,
+    /// which makes them suitable for ordering actions even when timestamps are not.
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// The time the action was created.
+    pub fn timestamp(&self) -> SystemTime {
+        self.timestamp
+    }
+
+    /// An identifier of what caused the action, e.g. a UI element.
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    /// An identifier shared by actions that are causally related.
+    pub fn correlation(&self) -> Option<u64> {
+        self.correlation
+    }
+}
+
+/// An action enveloped with [`Metadata`] (requires [`std`]).
+///
+/// [`Meta`] dereferences to the action it envelops, so middleware such as
+/// [`DispatcherExt::inspect`](crate::DispatcherExt::inspect) can read both.
+///
+/// [`std`]: index.html#optional-features
+///
+/// # Example
+///
+/// ```rust
+/// use reducer::*;
+///
+/// struct Click;
+///
+/// let click = Meta::new(Click).with_source("submit-button");
+/// let submit = click.follow_up("Submit");
+///
+/// let (click, submit) = (click.metadata(), submit.metadata());
+///
+/// assert_eq!(click.source(), Some("submit-button"));
+/// assert!(click.sequence() < submit.sequence());
+///
+/// // Follow-up actions are correlated with the action that caused them.
+/// assert_eq!(submit.correlation(), Some(click.sequence()));
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Deref, DerefMut)]
+pub struct Meta<A> {
+    metadata: Metadata,
+    #[deref]
+    #[deref_mut]
+    action: A,
+}
+
+impl<A> Meta<A> {
+    /// Envelopes an action, assigning it the next sequence number and the current time.
+    pub fn new(action: A) -> Self {
+        Meta {
+            metadata: Metadata {
+                sequence: SEQUENCE.fetch_add(1, Ordering::Relaxed),
+                timestamp: SystemTime::now(),
+                source: None,
+                correlation: None,
+            },
+            action,
+        }
+    }
+
+    /// Sets the identifier of what caused the action.
+    pub fn with_source(mut self, source: impl Into<Cow<'static, str>>) -> Self {
+        self.metadata.source = Some(source.into());
+        self
+    }
+
+    /// Sets the identifier shared by actions that are causally related.
+    pub fn with_correlation(mut self, correlation: u64) -> Self {
+        self.metadata.correlation = Some(correlation);
+        self
+    }
+
+    /// Envelopes an action caused by this one.
+    ///
+    /// The follow-up action shares the source and the correlation identifier of this action,
+    /// which defaults to this action's sequence number.
+    pub fn follow_up<B>(&self, action: B) -> Meta<B> {
+        let mut meta = Meta::new(action);
+        meta.metadata.source = self.metadata.source.clone();
+        meta.metadata.correlation =
+            Some(self.metadata.correlation.unwrap_or(self.metadata.sequence));
+        meta
+    }
+
+    /// The [`Metadata`] of the action.
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// Separates the action from its [`Metadata`].
+    pub fn into_parts(self) -> (A, Metadata) {
+        (self.action, self.metadata)
+    }
+
+    /// Returns the action, discarding its [`Metadata`].
+    pub fn into_inner(self) -> A {
+        self.action
+    }
+}
+
+/// Enhances a [`Reducer`] to handle actions enveloped in [`Meta`] (requires [`std`]).
+///
+/// [`WithMeta`] reduces the enveloped action, so the [`Reducer`] can remain oblivious
+/// of [`Metadata`], and keeps the [`Metadata`] of the latest action around,
+/// so [`Reactor`](crate::Reactor)s can tell what caused the state transition.
+///
+/// A blanket implementation of [`Reducer<Meta<A>>`] for all [`Reducer<A>`] is not possible,
+/// because it would conflict with the implementations for smart pointers and tuples.
+///
+/// [`std`]: index.html#optional-features
+///
+/// # Example
+///
+/// ```rust
+/// use reducer::*;
+/// use std::convert::Infallible;
+///
+/// #[derive(Default)]
+/// struct Counter(u32);
+///
+/// struct Increment;
+///
+/// impl Reducer<Increment> for Counter {
+///     fn reduce(&mut self, _: Increment) {
+///         self.0 += 1;
+///     }
+/// }
+///
+/// struct Audit(Vec<String>);
+///
+/// impl Reactor<WithMeta<Counter>> for Audit {
+///     type Error = Infallible;
+///
+///     fn react(&mut self, state: &WithMeta<Counter>) -> Result<(), Self::Error> {
+///         if let Some(source) = state.metadata().and_then(Metadata::source) {
+///             self.0.push(format!("{} set the counter to {}", source, state.0));
+///         }
+///
+///         Ok(())
+///     }
+/// }
+///
+/// let mut store = Store::new(WithMeta::new(Counter::default()), Audit(vec![]));
+///
+/// store.dispatch(Meta::new(Increment).with_source("plus-button")).unwrap();
+/// assert_eq!(store.0, 1);
+/// ```
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash, Deref)]
+pub struct WithMeta<S> {
+    #[deref]
+    state: S,
+    metadata: Option<Metadata>,
+}
+
+impl<S> WithMeta<S> {
+    /// Wraps the state.
+    pub fn new(state: S) -> Self {
+        WithMeta {
+            state,
+            metadata: None,
+        }
+    }
+
+    /// The [`Metadata`] of the latest action, if any.
+    pub fn metadata(&self) -> Option<&Metadata> {
+        self.metadata.as_ref()
+    }
+
+    /// Returns the state.
+    pub fn into_inner(self) -> S {
+        self.state
+    }
+}
+
+impl<A, S> Reducer<Meta<A>> for WithMeta<S>
+where
+    S: Reducer<A>,
+{
+    fn reduce(&mut self, action: Meta<A>) {
+        let (action, metadata) = action.into_parts();
+        self.state.reduce(action);
+        self.metadata = Some(metadata);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockall::predicate::*;
+    use std::string::String;
+    use test_strategy::proptest;
+
+    #[proptest]
+    fn meta(action: u8, source: String, correlation: u64) {
+        let meta = Meta::new(action)
+            .with_source(source.clone())
+            .with_correlation(correlation);
+
+        assert_eq!(*meta, action);
+        assert_eq!(meta.metadata().source(), Some(&*source));
+        assert_eq!(meta.metadata().correlation(), Some(correlation));
+        assert!(meta.metadata().timestamp() <= SystemTime::now());
+
+        let (a, metadata) = meta.clone().into_parts();
+        assert_eq!(a, action);
+        assert_eq!(&metadata, meta.metadata());
+        assert_eq!(meta.into_inner(), action);
+    }
+
+    #[proptest]
+    fn sequence(a: u8, b: u8) {
+        let a = Meta::new(a);
+        let b = Meta::new(b);
+        assert!(a.metadata().sequence() < b.metadata().sequence());
+    }
+
+    #[proptest]
+    fn follow_up(a: u8, b: u8, c: u8) {
+        let a = Meta::new(a).with_source("a");
+        let b = a.follow_up(b);
+        let c = b.follow_up(c);
+
+        assert_eq!(a.metadata().correlation(), None);
+        assert_eq!(b.metadata().source(), Some("a"));
+        assert_eq!(b.metadata().correlation(), Some(a.metadata().sequence()));
+        assert_eq!(c.metadata().correlation(), Some(a.metadata().sequence()));
+        assert!(b.metadata().sequence() < c.metadata().sequence());
+    }
+
+    #[proptest]
+    fn reduce(action: u8, id: usize) {
+        let mut mock = MockReducer::new();
+        mock.expect_id().return_const(id);
+        mock.expect_reduce()
+            .with(eq(action))
+            .once()
+            .return_const(());
+
+        let mut reducer = WithMeta::new(mock);
+        assert_eq!(reducer.metadata(), None);
+        assert_eq!(reducer.id(), id);
+
+        let meta = Meta::new(action);
+        let metadata = meta.metadata().clone();
+
+        reducer.reduce(meta);
+        assert_eq!(reducer.metadata(), Some(&metadata));
+        assert_eq!(reducer.into_inner().id(), id);
+    }
+}
@@ -0,0 +1,298 @@
+use crate::reducer::*;
+use alloc::{boxed::Box, collections::VecDeque};
+use core::fmt::{self, Debug, Formatter};
+use core::ops::Deref;
+
+/// An action applied optimistically by an [`Optimistic`] state (requires [`alloc`]).
+///
+/// The action remains pending until the authority either [confirms] or [rejects] it.
+///
+/// [`alloc`]: index.html#optional-features
+/// [confirms]: Confirm
+/// [rejects]: Reject
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Local<A>(pub A);
+
+/// An action the authority applied on behalf of someone else (requires [`alloc`]).
+///
+/// [`alloc`]: index.html#optional-features
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Remote<A>(pub A);
+
+/// Acknowledges that the authority applied the pending action with the given id
+/// (requires [`alloc`]).
+///
+/// [`alloc`]: index.html#optional-features
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Confirm(pub u64);
+
+/// Acknowledges that the authority discarded the pending action with the given id
+/// (requires [`alloc`]).
+///
+/// [`alloc`]: index.html#optional-features
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Reject(pub u64);
+
+struct Pending<S> {
+    id: u64,
+    replay: Box<dyn Fn(&mut S)>,
+}
+
+/// A state that applies pending [`Local`] actions on top of the state confirmed by an
+/// authority, such as a server (requires [`alloc`]).
+///
+/// [`Optimistic`] dereferences to the state as if all pending actions had succeeded,
+/// so the UI may reflect them immediately.
+/// Every [`Local`] action is assigned an id, sequentially from zero,
+/// which the authority refers to when it [confirms](Confirm) or [rejects](Reject) it.
+/// Whenever the confirmed state changes out of the order actions were applied locally,
+/// pending actions are replayed on top of it through [`Reducer::reduce`],
+/// so the [`Reactor`](crate::Reactor) is notified of the rebased state.
+///
+/// Replaying actions is only meaningful if the [`Reducer`] is deterministic.
+///
+/// [`alloc`]: index.html#optional-features
+///
+/// # Example
+///
+/// ```rust
+/// use reducer::*;
+///
+/// #[derive(Debug, Default, Clone)]
+/// struct Chat(Vec<&'static str>);
+///
+/// #[derive(Clone)]
+/// struct Post(&'static str);
+///
+/// impl Reducer<Post> for Chat {
+///     fn reduce(&mut self, Post(message): Post) {
+///         self.0.push(message);
+///     }
+/// }
+///
+/// let mut chat = Optimistic::new(Chat::default());
+///
+/// let id = chat.next_id();
+/// chat.reduce(Local(Post("hello?")));
+/// chat.reduce(Local(Post("anyone?")));
+/// assert_eq!(chat.0, ["hello?", "anyone?"]);
+///
+/// // Someone else's message reached the server first.
+/// chat.reduce(Remote(Post("hi!")));
+/// assert_eq!(chat.0, ["hi!", "hello?", "anyone?"]);
+///
+/// chat.reduce(Confirm(id));
+/// chat.reduce(Reject(id + 1));
+/// assert_eq!(chat.0, ["hi!", "hello?"]);
+/// assert_eq!(chat.confirmed().0, ["hi!", "hello?"]);
+/// ```
+pub struct Optimistic<S> {
+    confirmed: S,
+    state: S,
+    pending: VecDeque<Pending<S>>,
+    next: u64,
+}
+
+impl<S: Debug> Debug for Optimistic<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Optimistic")
+            .field("confirmed", &self.confirmed)
+            .field("state", &self.state)
+            .field("pending", &self.pending.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S: Clone + Default> Default for Optimistic<S> {
+    fn default() -> Self {
+        Self::new(S::default())
+    }
+}
+
+impl<S> Deref for Optimistic<S> {
+    type Target = S;
+
+    fn deref(&self) -> &S {
+        &self.state
+    }
+}
+
+impl<S: Clone> Optimistic<S> {
+    /// Wraps the state confirmed by the authority.
+    pub fn new(state: S) -> Self {
+        Optimistic {
+            confirmed: state.clone(),
+            state,
+            pending: VecDeque::new(),
+            next: 0,
+        }
+    }
+
+    /// The state confirmed by the authority, which doesn't account for pending actions.
+    pub fn confirmed(&self) -> &S {
+        &self.confirmed
+    }
+
+    /// The id the next [`Local`] action will be assigned.
+    pub fn next_id(&self) -> u64 {
+        self.next
+    }
+
+    /// The ids of the pending actions, in the order they were applied.
+    pub fn pending(&self) -> impl Iterator<Item = u64> + '_ {
+        self.pending.iter().map(|p| p.id)
+    }
+
+    /// Returns the state as if all pending actions had succeeded.
+    pub fn into_inner(self) -> S {
+        self.state
+    }
+
+    /// Recomputes the state by replaying pending actions on top of the confirmed state.
+    fn rebase(&mut self) {
+        self.state = self.confirmed.clone();
+
+        for pending in &self.pending {
+            (pending.replay)(&mut self.state);
+        }
+    }
+}
+
+impl<A, S> Reducer<Local<A>> for Optimistic<S>
+where
+    A: Clone + 'static,
+    S: Reducer<A> + Clone,
+{
+    fn reduce(&mut self, Local(action): Local<A>) {
+        let replay = action.clone();
+        self.pending.push_back(Pending {
+            id: self.next,
+            replay: Box::new(move |state: &mut S| state.reduce(replay.clone())),
+        });
+
+        self.next += 1;
+        self.state.reduce(action);
+    }
+}
+
+impl<A, S> Reducer<Remote<A>> for Optimistic<S>
+where
+    S: Reducer<A> + Clone,
+{
+    fn reduce(&mut self, Remote(action): Remote<A>) {
+        self.confirmed.reduce(action);
+
+        if self.pending.is_empty() {
+            self.state = self.confirmed.clone();
+        } else {
+            self.rebase();
+        }
+    }
+}
+
+/// Confirming an action that is not pending has no effect.
+impl<S: Clone> Reducer<Confirm> for Optimistic<S> {
+    fn reduce(&mut self, Confirm(id): Confirm) {
+        if let Some(i) = self.pending.iter().position(|p| p.id == id) {
+            if let Some(pending) = self.pending.remove(i) {
+                (pending.replay)(&mut self.confirmed);
+            }
+
+            // Confirming the earliest pending action doesn't change the state.
+            if i > 0 {
+                self.rebase();
+            }
+        }
+    }
+}
+
+/// Rejecting an action that is not pending has no effect.
+impl<S: Clone> Reducer<Reject> for Optimistic<S> {
+    fn reduce(&mut self, Reject(id): Reject) {
+        if let Some(i) = self.pending.iter().position(|p| p.id == id) {
+            self.pending.remove(i);
+            self.rebase();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec::Vec;
+    use test_strategy::{proptest, Arbitrary};
+
+    #[derive(Debug, Default, Clone, Eq, PartialEq)]
+    struct History(Vec<u8>);
+
+    impl Reducer<u8> for History {
+        fn reduce(&mut self, action: u8) {
+            self.0.push(action);
+        }
+    }
+
+    #[derive(Debug, Clone, Arbitrary)]
+    enum Op {
+        Local(u8),
+        Remote(u8),
+        Confirm(u64),
+        Reject(u64),
+    }
+
+    #[proptest]
+    fn default() {
+        let state = Optimistic::<History>::default();
+        assert_eq!(state.next_id(), 0);
+        assert_eq!(state.pending().count(), 0);
+        assert_eq!(*state, History::default());
+        assert_eq!(state.into_inner(), History::default());
+    }
+
+    #[proptest]
+    fn reconcile(state: Vec<u8>, ops: Vec<Op>) {
+        let mut optimistic = Optimistic::new(History(state.clone()));
+
+        let mut confirmed = state;
+        let mut pending: Vec<(u64, u8)> = Vec::new();
+
+        for op in ops {
+            match op {
+                Op::Local(action) => {
+                    pending.push((optimistic.next_id(), action));
+                    optimistic.reduce(Local(action));
+                }
+
+                Op::Remote(action) => {
+                    confirmed.push(action);
+                    optimistic.reduce(Remote(action));
+                }
+
+                Op::Confirm(id) => {
+                    let id = id % (optimistic.next_id() + 1);
+
+                    if let Some(i) = pending.iter().position(|p| p.0 == id) {
+                        confirmed.push(pending.remove(i).1);
+                    }
+
+                    optimistic.reduce(Confirm(id));
+                }
+
+                Op::Reject(id) => {
+                    let id = id % (optimistic.next_id() + 1);
+                    pending.retain(|p| p.0 != id);
+                    optimistic.reduce(Reject(id));
+                }
+            }
+
+            let mut expected = confirmed.clone();
+            expected.extend(pending.iter().map(|p| p.1));
+
+            assert_eq!(optimistic.confirmed(), &History(confirmed.clone()));
+            assert_eq!(*optimistic, History(expected));
+            assert_eq!(
+                optimistic.pending().collect::<Vec<_>>(),
+                pending.iter().map(|p| p.0).collect::<Vec<_>>()
+            );
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use crate::reducer::*;
 use alloc::rc::Rc;
 
-/// Enhances a potentially _unsized_ [`Reducer`] with copy-on-write semantics (requires [`alloc`]).
+/// Enhances a [`Reducer`] with copy-on-write semantics (requires [`alloc`]).
 ///
 /// Helps avoiding cloning the entire state when it needs to be sent to other parts of the
 /// application.
@@ -45,7 +45,7 @@ use alloc::rc::Rc;
 /// ```
 impl<A, T> Reducer<A> for Rc<T>
 where
-    T: Reducer<A> + Clone + ?Sized,
+    T: Reducer<A> + Clone,
 {
     fn reduce(&mut self, action: A) {
         Rc::make_mut(self).reduce(action);
@@ -0,0 +1,98 @@
+#[cfg(feature = "tracing")]
+pub(crate) use self::enabled::*;
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) use self::disabled::*;
+
+#[cfg(feature = "tracing")]
+mod enabled {
+    use core::any::type_name;
+    use core::fmt::Debug;
+
+    #[cfg(feature = "async")]
+    pub(crate) use tracing::Instrument;
+    pub(crate) use tracing::Span;
+
+    /// The span of dispatching an action of type `A`.
+    ///
+    /// Actions are identified by type name only,
+    /// since recording their `Debug` output would require all actions to implement `Debug`,
+    /// see [`dispatch_debug`] for those that do.
+    pub(crate) fn dispatch<A>() -> Span {
+        tracing::debug_span!("dispatch", action = type_name::<A>())
+    }
+
+    /// The span of dispatching an action of type `A`, which also records its `Debug` output.
+    pub(crate) fn dispatch_debug<A: Debug>(action: &A) -> Span {
+        tracing::debug_span!("dispatch", action = type_name::<A>(), action.debug = ?action)
+    }
+
+    /// The span of reducing an action.
+    pub(crate) fn reduce() -> Span {
+        tracing::trace_span!("reduce")
+    }
+
+    /// The span of notifying the reactor.
+    pub(crate) fn react() -> Span {
+        tracing::trace_span!("react")
+    }
+
+    /// Records that the reactor failed with an error of type `E`.
+    pub(crate) fn failed<E>() {
+        tracing::debug!(error = type_name::<E>(), "reactor failed");
+    }
+
+    /// Records that the reactor failed with `error`, including its `Debug` output.
+    pub(crate) fn failed_debug<E: Debug>(error: &E) {
+        tracing::debug!(error = type_name::<E>(), error.debug = ?error, "reactor failed");
+    }
+
+    /// Records that serving an IPC connection failed with `error`.
+    #[cfg(feature = "ipc")]
+    pub(crate) fn disconnected(error: &std::io::Error) {
+        tracing::warn!(%error, "connection failed");
+    }
+}
+
+#[cfg(not(feature = "tracing"))]
+mod disabled {
+    /// A no-op stand-in for `tracing::Span`.
+    #[derive(Debug, Clone)]
+    pub(crate) struct Span;
+
+    impl Span {
+        pub(crate) fn in_scope<T>(&self, f: impl FnOnce() -> T) -> T {
+            f()
+        }
+    }
+
+    /// A no-op stand-in for `tracing::Instrument`.
+    #[cfg(feature = "async")]
+    pub(crate) trait Instrument: Sized {
+        fn instrument(self, _: Span) -> Self {
+            self
+        }
+    }
+
+    #[cfg(feature = "async")]
+    impl<T> Instrument for T {}
+
+    #[allow(clippy::extra_unused_type_parameters)]
+    pub(crate) fn dispatch<A>() -> Span {
+        Span
+    }
+
+    pub(crate) fn reduce() -> Span {
+        Span
+    }
+
+    pub(crate) fn react() -> Span {
+        Span
+    }
+
+    #[allow(clippy::extra_unused_type_parameters)]
+    pub(crate) fn failed<E>() {}
+
+    #[cfg(feature = "ipc")]
+    pub(crate) fn disconnected(_: &std::io::Error) {}
+}
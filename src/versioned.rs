@@ -0,0 +1,282 @@
+use crate::dispatcher::Store;
+use derive_more::Display;
+use serde::de::{DeserializeOwned, Error as _};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::boxed::Box;
+use std::io::{Read, Write};
+
+/// The error returned when a persisted state can't be restored (requires [`versioning`]).
+///
+/// [`versioning`]: index.html#optional-features
+#[derive(Debug, Display)]
+pub enum VersionError {
+    /// The snapshot is malformed or doesn't match the schema of its version.
+    #[display(fmt = "{}", _0)]
+    Format(serde_json::Error),
+
+    /// The snapshot is either newer than the latest version or older than the earliest one.
+    #[display(fmt = "Unsupported version {} of the state", _0)]
+    Unsupported(u32),
+
+    /// A [migration](Versioned::migrate) failed.
+    #[display(fmt = "Failed to migrate the state from version {} to {}", from, to)]
+    Migration {
+        /// The version migrated from.
+        from: u32,
+        /// The version migrated to.
+        to: u32,
+        /// The reason the migration failed.
+        reason: Box<dyn std::error::Error + Send + Sync>,
+    },
+}
+
+impl std::error::Error for VersionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            VersionError::Format(e) => Some(e),
+            VersionError::Unsupported(_) => None,
+            VersionError::Migration { reason, .. } => Some(&**reason),
+        }
+    }
+}
+
+impl From<serde_json::Error> for VersionError {
+    fn from(error: serde_json::Error) -> Self {
+        VersionError::Format(error)
+    }
+}
+
+/// Trait for states whose schema evolves over time (requires [`versioning`]).
+///
+/// Every version of the state is a distinct type, which names the version it evolved from,
+/// so snapshots saved by any earlier version can be [loaded] by migrating them one version
+/// at a time, e.g. `v1 -> v2 -> v3`.
+/// The earliest version names itself as its previous version.
+///
+/// Snapshots are JSON objects that record the version alongside the state.
+///
+/// [`versioning`]: index.html#optional-features
+/// [loaded]: Versioned::load
+///
+/// # Example
+///
+/// ```rust
+/// use reducer::*;
+/// use serde::{Deserialize, Serialize};
+/// use std::error::Error;
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct SettingsV1 {
+///     volume: u8, // 0 to 100
+/// }
+///
+/// impl Versioned for SettingsV1 {
+///     const VERSION: u32 = 1;
+///     type Previous = Self;
+///
+///     fn migrate(previous: Self) -> Result<Self, Box<dyn Error + Send + Sync>> {
+///         Ok(previous)
+///     }
+/// }
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Settings {
+///     volume: f32, // 0.0 to 1.0
+///     muted: bool,
+/// }
+///
+/// impl Versioned for Settings {
+///     const VERSION: u32 = 2;
+///     type Previous = SettingsV1;
+///
+///     fn migrate(SettingsV1 { volume }: SettingsV1) -> Result<Self, Box<dyn Error + Send + Sync>> {
+///         Ok(Settings {
+///             volume: f32::from(volume) / 100.,
+///             muted: volume == 0,
+///         })
+///     }
+/// }
+///
+/// let saved = br#"{"version": 1, "state": {"volume": 50}}"#;
+///
+/// let settings = Settings::load(&saved[..]).unwrap();
+/// assert_eq!(settings, Settings { volume: 0.5, muted: false });
+///
+/// let mut snapshot = Vec::new();
+/// settings.save(&mut snapshot).unwrap();
+/// assert_eq!(Settings::load(&snapshot[..]).unwrap(), settings);
+/// ```
+pub trait Versioned: Serialize + DeserializeOwned {
+    /// The version of the schema, which must be greater than that of the previous version.
+    const VERSION: u32;
+
+    /// The version this one evolved from.
+    type Previous: Versioned;
+
+    /// Migrates the state from the previous version.
+    fn migrate(previous: Self::Previous) -> Result<Self, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// Saves a snapshot of the state.
+    fn save<W: Write>(&self, writer: W) -> Result<(), VersionError> {
+        let snapshot = json!({ "version": Self::VERSION, "state": self });
+        Ok(serde_json::to_writer(writer, &snapshot)?)
+    }
+
+    /// Loads a snapshot saved by this or any earlier version of the state,
+    /// migrating it as necessary.
+    fn load<R: Read>(reader: R) -> Result<Self, VersionError> {
+        let mut snapshot: Value = serde_json::from_reader(reader)?;
+
+        let version = snapshot["version"]
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| serde_json::Error::custom("expected a version"))?;
+
+        migrate(version, snapshot["state"].take())
+    }
+}
+
+/// Deserializes the state as of `version` and migrates it up to `S`.
+fn migrate<S: Versioned>(version: u32, state: Value) -> Result<S, VersionError> {
+    if version == S::VERSION {
+        Ok(serde_json::from_value(state)?)
+    } else if version < S::VERSION && S::Previous::VERSION < S::VERSION {
+        let previous = migrate::<S::Previous>(version, state)?;
+
+        S::migrate(previous).map_err(|reason| VersionError::Migration {
+            from: S::Previous::VERSION,
+            to: S::VERSION,
+            reason,
+        })
+    } else {
+        Err(VersionError::Unsupported(version))
+    }
+}
+
+impl<S: Versioned, R> Store<S, R> {
+    /// Constructs the Store given a snapshot saved by any version of the state and a
+    /// [`Reactor`](crate::Reactor) (requires [`versioning`]).
+    ///
+    /// See [`Versioned::load`].
+    ///
+    /// [`versioning`]: index.html#optional-features
+    pub fn restore<T: Read>(reader: T, reactor: R) -> Result<Self, VersionError> {
+        Ok(Store::new(S::load(reader)?, reactor))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reactor::MockReactor;
+    use serde::Deserialize;
+    use std::string::{String, ToString};
+    use std::vec::Vec;
+    use test_strategy::proptest;
+
+    #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+    struct V1(u8);
+
+    impl Versioned for V1 {
+        const VERSION: u32 = 1;
+        type Previous = Self;
+
+        fn migrate(previous: Self) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+            Ok(previous)
+        }
+    }
+
+    #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+    struct V2(u16);
+
+    impl Versioned for V2 {
+        const VERSION: u32 = 2;
+        type Previous = V1;
+
+        fn migrate(V1(x): V1) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+            Ok(V2(u16::from(x) * 2))
+        }
+    }
+
+    #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+    struct V3(String);
+
+    impl Versioned for V3 {
+        const VERSION: u32 = 5;
+        type Previous = V2;
+
+        fn migrate(V2(x): V2) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+            match x {
+                0 => Err("zero is not supported".into()),
+                x => Ok(V3(x.to_string())),
+            }
+        }
+    }
+
+    fn snapshot<S: Versioned>(state: &S) -> Result<Vec<u8>, VersionError> {
+        let mut snapshot = Vec::new();
+        state.save(&mut snapshot)?;
+        Ok(snapshot)
+    }
+
+    #[proptest]
+    fn roundtrip(state: String) {
+        let state = V3(state);
+        assert_eq!(V3::load(&snapshot(&state)?[..])?, state);
+    }
+
+    #[proptest]
+    fn migrate(#[strategy(1u8..)] x: u8) {
+        assert_eq!(V2::load(&snapshot(&V1(x))?[..])?, V2(u16::from(x) * 2));
+        assert_eq!(
+            V3::load(&snapshot(&V1(x))?[..])?,
+            V3((u16::from(x) * 2).to_string())
+        );
+        assert_eq!(V3::load(&snapshot(&V2(x.into()))?[..])?, V3(x.to_string()));
+    }
+
+    #[proptest]
+    fn restore(#[strategy(1u8..)] x: u8) {
+        let store =
+            Store::<V3, MockReactor<V3, ()>>::restore(&snapshot(&V1(x))?[..], MockReactor::new())?;
+        assert_eq!(*store, V3((u16::from(x) * 2).to_string()));
+    }
+
+    #[proptest]
+    fn unsupported(#[strategy(3u32..5)] version: u32, #[strategy(6u32..)] newer: u32) {
+        for version in [0, version, newer] {
+            let snapshot = json!({ "version": version, "state": "" }).to_string();
+
+            let result = V3::load(snapshot.as_bytes());
+            assert!(matches!(result, Err(VersionError::Unsupported(v)) if v == version));
+        }
+
+        let result = V1::load(&snapshot(&V2(0))?[..]);
+        assert!(matches!(result, Err(VersionError::Unsupported(2))));
+    }
+
+    #[proptest]
+    fn failure() {
+        let error = V3::load(&snapshot(&V1(0))?[..]).unwrap_err();
+        assert!(matches!(
+            error,
+            VersionError::Migration { from: 2, to: 5, .. }
+        ));
+
+        let reason = std::error::Error::source(&error).map(ToString::to_string);
+        assert_eq!(reason.as_deref(), Some("zero is not supported"));
+    }
+
+    #[proptest]
+    fn format(x: u8) {
+        let result = V3::load(&snapshot(&V1(x))?[..2]);
+        assert!(matches!(result, Err(VersionError::Format(_))));
+
+        let result = V3::load(&br#"{"state": "0"}"#[..]);
+        assert!(matches!(result, Err(VersionError::Format(_))));
+
+        let result = V2::load(json!({ "version": 2, "state": "0" }).to_string().as_bytes());
+        assert!(matches!(result, Err(VersionError::Format(_))));
+    }
+}